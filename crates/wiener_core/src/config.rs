@@ -0,0 +1,208 @@
+use crate::{ActionMap, ConfigError, InputRecording, WindowDescriptor};
+
+use log;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use toml;
+
+/// Load a value from a TOML file.
+fn load_toml_file<T: DeserializeOwned>(path: &Path) -> Result<T, ConfigError> {
+    log::info!("load_toml_file :: Loading {:?}", path);
+    return Ok(toml::from_str(&fs::read_to_string(path)?)?);
+}
+
+/// Save a value to a TOML file, overwriting it if it exists.
+fn save_toml_file<T: Serialize>(value: &T, path: &Path) -> Result<(), ConfigError> {
+    log::info!("save_toml_file :: Saving {:?}", path);
+    fs::write(path, toml::to_string_pretty(value)?)?;
+    return Ok(());
+}
+
+impl WindowDescriptor {
+    /// Parse a descriptor from a TOML string. Missing fields take their
+    /// default values.
+    pub fn from_toml(contents: &str) -> Result<Self, ConfigError> {
+        return Ok(toml::from_str(contents)?);
+    }
+
+    /// Serialize the descriptor into a TOML string.
+    pub fn to_toml(&self) -> Result<String, ConfigError> {
+        return Ok(toml::to_string_pretty(self)?);
+    }
+
+    /// Load a descriptor from a TOML file. Missing fields take their default
+    /// values.
+    pub fn from_toml_file<P: AsRef<Path>>(path: P) -> Result<Self, ConfigError> {
+        return load_toml_file(path.as_ref());
+    }
+
+    /// Save the descriptor to a TOML file, overwriting it if it exists.
+    pub fn to_toml_file<P: AsRef<Path>>(&self, path: P) -> Result<(), ConfigError> {
+        return save_toml_file(self, path.as_ref());
+    }
+}
+
+impl ActionMap {
+    /// Parse the bindings of an action map from a TOML string.
+    pub fn from_toml(contents: &str) -> Result<Self, ConfigError> {
+        return Ok(toml::from_str(contents)?);
+    }
+
+    /// Serialize the bindings of the action map into a TOML string.
+    pub fn to_toml(&self) -> Result<String, ConfigError> {
+        return Ok(toml::to_string_pretty(self)?);
+    }
+
+    /// Load the bindings of an action map from a TOML file.
+    pub fn from_toml_file<P: AsRef<Path>>(path: P) -> Result<Self, ConfigError> {
+        return load_toml_file(path.as_ref());
+    }
+
+    /// Save the bindings of the action map to a TOML file, overwriting it if
+    /// it exists.
+    pub fn to_toml_file<P: AsRef<Path>>(&self, path: P) -> Result<(), ConfigError> {
+        return save_toml_file(self, path.as_ref());
+    }
+}
+
+impl InputRecording {
+    /// Load a recording from a TOML file.
+    pub fn from_toml_file<P: AsRef<Path>>(path: P) -> Result<Self, ConfigError> {
+        return load_toml_file(path.as_ref());
+    }
+
+    /// Save the recording to a TOML file, overwriting it if it exists.
+    pub fn to_toml_file<P: AsRef<Path>>(&self, path: P) -> Result<(), ConfigError> {
+        return save_toml_file(self, path.as_ref());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Action, Binding, CursorMode, Event, GamepadAxis, GamepadButton, InputSource, Key,
+        Modifiers, MouseButton, SwapInterval, WindowMode,
+    };
+    use std::path::PathBuf;
+
+    #[test]
+    fn window_descriptor_round_trips() {
+        let descriptor = WindowDescriptor {
+            width: 1280,
+            height: 720,
+            title: "Round trip".to_string(),
+            mode: WindowMode::FullScreen(1),
+            swap_interval: SwapInterval::Sync(2),
+            cursor_mode: CursorMode::Disabled,
+            samples: 4,
+            ..Default::default()
+        };
+        let contents = descriptor.to_toml().unwrap();
+        assert_eq!(WindowDescriptor::from_toml(&contents).unwrap(), descriptor);
+    }
+
+    #[test]
+    fn window_modes_round_trip() {
+        for mode in [
+            WindowMode::Windowed,
+            WindowMode::FullScreen(0),
+            WindowMode::FullScreen(2),
+            WindowMode::Borderless(1),
+        ] {
+            let descriptor = WindowDescriptor {
+                mode,
+                ..Default::default()
+            };
+            let contents = descriptor.to_toml().unwrap();
+            assert_eq!(WindowDescriptor::from_toml(&contents).unwrap().mode, mode);
+        }
+    }
+
+    #[test]
+    fn missing_window_fields_take_default_values() {
+        let descriptor = WindowDescriptor::from_toml("width = 640\ntitle = \"Small\"").unwrap();
+        assert_eq!(descriptor.width, 640);
+        assert_eq!(descriptor.title, "Small");
+        assert_eq!(descriptor.height, WindowDescriptor::default().height);
+    }
+
+    #[test]
+    fn invalid_window_descriptor_is_an_error() {
+        assert!(WindowDescriptor::from_toml("width = \"wide\"").is_err());
+    }
+
+    #[test]
+    fn action_map_round_trips() {
+        let map = ActionMap::new()
+            .bind(
+                "save",
+                Binding::new(InputSource::Key(Key::S)).set_modifiers(Modifiers {
+                    control: true,
+                    ..Default::default()
+                }),
+            )
+            .bind(
+                "orbit",
+                Binding::chord(&[
+                    InputSource::MouseButton(MouseButton::Left),
+                    InputSource::MouseX,
+                ])
+                .set_scale(0.5)
+                .set_any_modifiers(true),
+            )
+            .bind(
+                "throttle",
+                Binding::new(InputSource::GamepadAxis(GamepadAxis::RightTrigger)),
+            );
+        let contents = map.to_toml().unwrap();
+        assert_eq!(
+            ActionMap::from_toml(&contents).unwrap().bindings,
+            map.bindings
+        );
+    }
+
+    #[test]
+    fn input_recording_round_trips() {
+        let mut recording = InputRecording::new();
+        recording.push_frame(
+            0.5,
+            &[
+                Event::Key {
+                    key: Key::A,
+                    scancode: 38,
+                    action: Action::Press,
+                    modifiers: Modifiers {
+                        shift: true,
+                        ..Default::default()
+                    },
+                },
+                Event::CursorMoved { x: 10.5, y: 20.0 },
+                Event::Focused(true),
+                Event::Close,
+            ],
+        );
+        recording.push_frame(0.75, &[]);
+        recording.push_frame(
+            1.0,
+            &[
+                Event::GamepadConnected(0),
+                Event::GamepadButton {
+                    id: 0,
+                    button: GamepadButton::Start,
+                    action: Action::Release,
+                },
+                Event::FileDrop(vec![PathBuf::from("scene.obj")]),
+            ],
+        );
+
+        let path =
+            std::env::temp_dir().join(format!("wiener_recording_{}.toml", std::process::id()));
+        recording.to_toml_file(&path).unwrap();
+        let loaded = InputRecording::from_toml_file(&path);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.unwrap(), recording);
+    }
+}
@@ -0,0 +1,126 @@
+use glfw;
+use std::error::Error;
+use std::fmt;
+
+/// Error produced while creating a window or its context.
+#[derive(Clone, Debug, PartialEq)]
+pub enum WindowError {
+    /// GLFW could not be initialized.
+    GlfwInit(glfw::InitError),
+
+    /// None of the requested context versions is available with the
+    /// requested profile.
+    UnsupportedContext {
+        versions: Vec<(u32, u32)>,
+        profile: glfw::OpenGlProfileHint,
+    },
+
+    /// The requested monitor could not be found.
+    MonitorNotFound(u32),
+
+    /// The window could not be created for any other reason, with the
+    /// description reported by GLFW.
+    WindowCreation(String),
+
+    /// EGL could not be loaded or a headless context could not be created,
+    /// with a description of the failure.
+    Egl(String),
+
+    /// An image for the window (e.g. its icon or cursor) could not be
+    /// loaded.
+    Image { path: String, description: String },
+}
+
+impl fmt::Display for WindowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WindowError::GlfwInit(e) => write!(f, "could not initialize GLFW ({e})"),
+            WindowError::UnsupportedContext { versions, profile } => write!(
+                f,
+                "none of the context versions {versions:?} is available with profile {profile:?}"
+            ),
+            WindowError::MonitorNotFound(monitor) => write!(f, "monitor {monitor} was not found"),
+            WindowError::WindowCreation(description) => {
+                write!(f, "could not create the window ({description})")
+            }
+            WindowError::Egl(description) => {
+                write!(f, "could not create the headless context ({description})")
+            }
+            WindowError::Image { path, description } => {
+                write!(f, "could not load the image {path:?} ({description})")
+            }
+        }
+    }
+}
+
+impl Error for WindowError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            WindowError::GlfwInit(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<glfw::InitError> for WindowError {
+    fn from(e: glfw::InitError) -> Self {
+        return WindowError::GlfwInit(e);
+    }
+}
+
+/// Error produced while loading or saving a configuration file.
+#[cfg(feature = "serde")]
+#[derive(Debug)]
+pub enum ConfigError {
+    /// The file could not be read or written.
+    Io(std::io::Error),
+
+    /// The contents of the file are not a valid configuration.
+    Deserialize(toml::de::Error),
+
+    /// The configuration could not be serialized.
+    Serialize(toml::ser::Error),
+}
+
+#[cfg(feature = "serde")]
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(e) => write!(f, "could not access the configuration file ({e})"),
+            ConfigError::Deserialize(e) => write!(f, "invalid configuration ({e})"),
+            ConfigError::Serialize(e) => write!(f, "could not serialize the configuration ({e})"),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Error for ConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ConfigError::Io(e) => Some(e),
+            ConfigError::Deserialize(e) => Some(e),
+            ConfigError::Serialize(e) => Some(e),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<std::io::Error> for ConfigError {
+    fn from(e: std::io::Error) -> Self {
+        return ConfigError::Io(e);
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<toml::de::Error> for ConfigError {
+    fn from(e: toml::de::Error) -> Self {
+        return ConfigError::Deserialize(e);
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<toml::ser::Error> for ConfigError {
+    fn from(e: toml::ser::Error) -> Self {
+        return ConfigError::Serialize(e);
+    }
+}
@@ -0,0 +1,210 @@
+use crate::WindowError;
+
+use glfw;
+use khronos_egl as egl;
+use log;
+use std::ffi::c_void;
+
+/// Platform of the Mesa surfaceless display, which needs no display server.
+const PLATFORM_SURFACELESS_MESA: egl::Enum = 0x31DD;
+
+/// OpenGL context created through EGL without any window, surface or display
+/// server, so it can render to framebuffers on machines with no X server
+/// (e.g. CI runners or render farms).
+///
+/// The context has no default framebuffer, so everything must be drawn to a
+/// framebuffer object. It is destroyed when dropped.
+pub struct HeadlessContext {
+    _egl: egl::DynamicInstance<egl::EGL1_5>,
+    _display: egl::Display,
+    _context: egl::Context,
+}
+
+impl std::fmt::Debug for HeadlessContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return f
+            .debug_struct("HeadlessContext")
+            .field("display", &self._display)
+            .field("context", &self._context)
+            .finish();
+    }
+}
+
+impl HeadlessContext {
+    /// Make the context current on this thread, without any surface.
+    pub fn make_current(&self) -> Result<(), WindowError> {
+        return self
+            ._egl
+            .make_current(self._display, None, None, Some(self._context))
+            .map_err(|e| WindowError::Egl(format!("could not make the context current ({e})")));
+    }
+
+    /// Get whether the context is the current one on this thread or not.
+    pub fn is_current(&self) -> bool {
+        return self._egl.get_current_context() == Some(self._context);
+    }
+
+    /// Get the address of an OpenGL function, or null if it is not available.
+    pub fn get_proc_address(&self, name: &str) -> *const c_void {
+        return match self._egl.get_proc_address(name) {
+            Some(f) => f as *const c_void,
+            None => std::ptr::null(),
+        };
+    }
+}
+
+impl Drop for HeadlessContext {
+    fn drop(&mut self) {
+        log::info!("HeadlessContext :: Destroying context");
+        if self.is_current() {
+            let _ = self._egl.make_current(self._display, None, None, None);
+        }
+        let _ = self._egl.destroy_context(self._display, self._context);
+        let _ = self._egl.terminate(self._display);
+    }
+}
+
+/// Get the surfaceless display if the EGL implementation supports it, or the
+/// default display otherwise.
+fn get_display(instance: &egl::DynamicInstance<egl::EGL1_5>) -> Result<egl::Display, WindowError> {
+    let extensions = instance
+        .query_string(None, egl::EXTENSIONS)
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    if extensions.contains("EGL_MESA_platform_surfaceless") {
+        log::info!("init_headless :: Using the surfaceless platform");
+        let display = unsafe {
+            instance.get_platform_display(
+                PLATFORM_SURFACELESS_MESA,
+                egl::DEFAULT_DISPLAY,
+                &[egl::ATTRIB_NONE],
+            )
+        };
+        if let Ok(display) = display {
+            return Ok(display);
+        }
+    }
+    log::info!("init_headless :: Using the default display");
+    return unsafe { instance.get_display(egl::DEFAULT_DISPLAY) }
+        .ok_or_else(|| WindowError::Egl("no EGL display is available".to_string()));
+}
+
+/// Get the context attributes for a version and profile.
+fn context_attributes(
+    version: (u32, u32),
+    profile: glfw::OpenGlProfileHint,
+    debug: bool,
+) -> Vec<egl::Int> {
+    let mut attributes = vec![
+        egl::CONTEXT_MAJOR_VERSION,
+        version.0 as egl::Int,
+        egl::CONTEXT_MINOR_VERSION,
+        version.1 as egl::Int,
+    ];
+    match profile {
+        glfw::OpenGlProfileHint::Core => attributes.extend([
+            egl::CONTEXT_OPENGL_PROFILE_MASK,
+            egl::CONTEXT_OPENGL_CORE_PROFILE_BIT,
+        ]),
+        glfw::OpenGlProfileHint::Compat => attributes.extend([
+            egl::CONTEXT_OPENGL_PROFILE_MASK,
+            egl::CONTEXT_OPENGL_COMPATIBILITY_PROFILE_BIT,
+        ]),
+        glfw::OpenGlProfileHint::Any => {}
+    }
+    if debug {
+        attributes.extend([egl::CONTEXT_OPENGL_DEBUG, egl::TRUE as egl::Int]);
+    }
+    attributes.push(egl::NONE);
+    return attributes;
+}
+
+/// Initializes a surfaceless OpenGL context through EGL, setting it as the
+/// current one. No window is created and no display server is needed, but
+/// the EGL library (`libEGL.so.1`) and a driver supporting
+/// `EGL_KHR_surfaceless_context` must be installed, such as Mesa (whose
+/// software rasterizer can be forced with `LIBGL_ALWAYS_SOFTWARE=1`).
+///
+/// The context versions are tried in order, and the first one that can be
+/// created with the given profile is used.
+pub fn init_headless(
+    versions: &[(u32, u32)],
+    profile: glfw::OpenGlProfileHint,
+    debug: bool,
+) -> Result<HeadlessContext, WindowError> {
+    log::info!("init_headless :: Loading EGL");
+    let instance = unsafe { egl::DynamicInstance::<egl::EGL1_5>::load_required() }
+        .map_err(|e| WindowError::Egl(format!("could not load EGL 1.5 ({e})")))?;
+    let display = get_display(&instance)?;
+    let (major, minor) = instance
+        .initialize(display)
+        .map_err(|e| WindowError::Egl(format!("could not initialize the display ({e})")))?;
+    log::info!("init_headless :: Initialized EGL {:?}.{:?}", major, minor);
+
+    let result = create_context(&instance, display, versions, profile, debug);
+    let context = match result {
+        Ok(context) => context,
+        Err(e) => {
+            let _ = instance.terminate(display);
+            return Err(e);
+        }
+    };
+    let headless = HeadlessContext {
+        _egl: instance,
+        _display: display,
+        _context: context,
+    };
+    headless.make_current()?;
+    return Ok(headless);
+}
+
+/// Create a context with the first available version.
+fn create_context(
+    instance: &egl::DynamicInstance<egl::EGL1_5>,
+    display: egl::Display,
+    versions: &[(u32, u32)],
+    profile: glfw::OpenGlProfileHint,
+    debug: bool,
+) -> Result<egl::Context, WindowError> {
+    let extensions = instance
+        .query_string(Some(display), egl::EXTENSIONS)
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    if !extensions.contains("EGL_KHR_surfaceless_context") {
+        return Err(WindowError::Egl(
+            "the display does not support surfaceless contexts".to_string(),
+        ));
+    }
+    instance
+        .bind_api(egl::OPENGL_API)
+        .map_err(|e| WindowError::Egl(format!("OpenGL is not supported ({e})")))?;
+    let config = instance
+        .choose_first_config(
+            display,
+            &[
+                egl::SURFACE_TYPE,
+                egl::PBUFFER_BIT,
+                egl::RENDERABLE_TYPE,
+                egl::OPENGL_BIT,
+                egl::NONE,
+            ],
+        )
+        .map_err(|e| WindowError::Egl(format!("could not choose a config ({e})")))?
+        .ok_or_else(|| WindowError::Egl("no config supports OpenGL".to_string()))?;
+
+    for version in versions {
+        log::info!(
+            "init_headless :: Trying context version {:?}.{:?}",
+            version.0,
+            version.1
+        );
+        let attributes = context_attributes(*version, profile, debug);
+        if let Ok(context) = instance.create_context(display, config, None, &attributes) {
+            return Ok(context);
+        }
+    }
+    return Err(WindowError::UnsupportedContext {
+        versions: versions.to_vec(),
+        profile,
+    });
+}
@@ -0,0 +1,457 @@
+use crate::{GamepadAxis, GamepadButton, InputState, Key, Modifiers, MouseButton};
+
+use std::collections::{BTreeMap, HashMap};
+
+/// Magnitude an analog value needs to reach for its action to count as held.
+const HELD_THRESHOLD: f32 = 0.5;
+
+/// Physical input that can be bound to an action.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum InputSource {
+    Key(Key),
+    MouseButton(MouseButton),
+    /// Button of any connected gamepad.
+    GamepadButton(GamepadButton),
+    /// Axis of any connected gamepad, taking the value furthest from 0.
+    GamepadAxis(GamepadAxis),
+    /// Horizontal movement of the cursor since the last frame.
+    MouseX,
+    /// Vertical movement of the cursor since the last frame.
+    MouseY,
+    /// Horizontal scroll since the last frame.
+    ScrollX,
+    /// Vertical scroll since the last frame.
+    ScrollY,
+}
+
+impl InputSource {
+    /// Get the value of the input, which is 1 or 0 for buttons.
+    fn get_value(&self, input: &InputState) -> f32 {
+        let digital = |active: bool| if active { 1.0 } else { 0.0 };
+        return match self {
+            InputSource::Key(key) => {
+                digital(input.is_key_held(*key) || input.was_key_pressed(*key))
+            }
+            InputSource::MouseButton(button) => digital(
+                input.is_mouse_button_held(*button) || input.was_mouse_button_pressed(*button),
+            ),
+            InputSource::GamepadButton(button) => digital(input.get_gamepads().iter().any(|id| {
+                input.is_gamepad_button_held(*id, *button)
+                    || input.was_gamepad_button_pressed(*id, *button)
+            })),
+            InputSource::GamepadAxis(axis) => input
+                .get_gamepads()
+                .iter()
+                .map(|id| input.get_gamepad_axis(*id, *axis))
+                .fold(0.0, |a, b| if b.abs() > a.abs() { b } else { a }),
+            InputSource::MouseX => input.get_mouse_delta().0 as f32,
+            InputSource::MouseY => input.get_mouse_delta().1 as f32,
+            InputSource::ScrollX => input.get_scroll_delta().0 as f32,
+            InputSource::ScrollY => input.get_scroll_delta().1 as f32,
+        };
+    }
+}
+
+/// Binding of an action to one or more inputs.
+///
+/// The value of a binding is the product of the values of its inputs, so a
+/// chord (e.g. `Control` + `S`, or a mouse button + `MouseX`) is only active
+/// while every button in it is held.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Binding {
+    /// Inputs that make up the binding.
+    pub inputs: Vec<InputSource>,
+
+    /// Modifiers that must be held for the binding to be active. Only shift,
+    /// control, alt and super are checked, and unless `any_modifiers` is set
+    /// the ones not listed must be released, so `Control` + `S` does not
+    /// trigger a binding to `S`. Modifiers whose keys are inputs of the
+    /// binding are not checked.
+    pub modifiers: Modifiers,
+
+    /// Whether the binding stays active while modifiers other than the
+    /// required ones are held.
+    pub any_modifiers: bool,
+
+    /// Factor applied to the value of the binding, e.g. -1 to make a key
+    /// move an analog action backwards.
+    pub scale: f32,
+}
+
+impl Binding {
+    /// Create a binding to a single input.
+    pub fn new(input: InputSource) -> Self {
+        return Binding {
+            inputs: vec![input],
+            ..Default::default()
+        };
+    }
+
+    /// Create a binding to a chord of inputs.
+    pub fn chord(inputs: &[InputSource]) -> Self {
+        return Binding {
+            inputs: inputs.to_vec(),
+            ..Default::default()
+        };
+    }
+
+    /// Set the modifiers that must be held.
+    pub fn set_modifiers(mut self, modifiers: Modifiers) -> Self {
+        self.modifiers = modifiers;
+        return self;
+    }
+
+    /// Set whether the binding stays active while other modifiers are held.
+    pub fn set_any_modifiers(mut self, any_modifiers: bool) -> Self {
+        self.any_modifiers = any_modifiers;
+        return self;
+    }
+
+    /// Set the factor applied to the value.
+    pub fn set_scale(mut self, scale: f32) -> Self {
+        self.scale = scale;
+        return self;
+    }
+
+    /// Get the value of the binding.
+    fn get_value(&self, input: &InputState) -> f32 {
+        if self.inputs.is_empty() || !self.modifiers_match(input) {
+            return 0.0;
+        }
+        return self
+            .inputs
+            .iter()
+            .map(|i| i.get_value(input))
+            .product::<f32>()
+            * self.scale;
+    }
+
+    /// Get whether the held modifiers match the required ones, looking at
+    /// both keys of each modifier.
+    fn modifiers_match(&self, input: &InputState) -> bool {
+        let matches = |required: bool, left: Key, right: Key| {
+            let held = input.is_key_held(left) || input.is_key_held(right);
+            if required {
+                return held;
+            }
+            let bound = self.inputs.contains(&InputSource::Key(left))
+                || self.inputs.contains(&InputSource::Key(right));
+            return !held || bound || self.any_modifiers;
+        };
+        return matches(self.modifiers.shift, Key::LeftShift, Key::RightShift)
+            && matches(self.modifiers.control, Key::LeftControl, Key::RightControl)
+            && matches(self.modifiers.alt, Key::LeftAlt, Key::RightAlt)
+            && matches(self.modifiers.super_key, Key::LeftSuper, Key::RightSuper);
+    }
+}
+
+impl Default for Binding {
+    fn default() -> Self {
+        return Binding {
+            inputs: Vec::new(),
+            modifiers: Modifiers::default(),
+            any_modifiers: false,
+            scale: 1.0,
+        };
+    }
+}
+
+/// State of an action in the current frame.
+#[derive(Clone, Copy, Debug, Default)]
+struct ActionState {
+    value: f32,
+    held: bool,
+    pressed: bool,
+    released: bool,
+}
+
+/// Map from logical actions (e.g. "jump" or "orbit") to the inputs that
+/// trigger them.
+///
+/// Every action has an analog value, which is the sum of the values of its
+/// bindings, and counts as held while that value is at least 0.5 in
+/// magnitude. The map must be updated once per frame, after polling events.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct ActionMap {
+    /// Bindings of each action.
+    pub bindings: BTreeMap<String, Vec<Binding>>,
+
+    #[cfg_attr(feature = "serde", serde(skip))]
+    _states: HashMap<String, ActionState>,
+}
+
+impl ActionMap {
+    /// Create an empty action map.
+    pub fn new() -> Self {
+        return ActionMap::default();
+    }
+
+    /// Add a binding to an action, creating the action if needed.
+    pub fn bind(mut self, action: &str, binding: Binding) -> Self {
+        self.add_binding(action, binding);
+        return self;
+    }
+
+    /// Add a binding to an action, creating the action if needed.
+    pub fn add_binding(&mut self, action: &str, binding: Binding) {
+        self.bindings
+            .entry(action.to_string())
+            .or_default()
+            .push(binding);
+    }
+
+    /// Replace every binding of an action.
+    pub fn set_bindings(&mut self, action: &str, bindings: Vec<Binding>) {
+        self.bindings.insert(action.to_string(), bindings);
+    }
+
+    /// Get the bindings of an action.
+    pub fn get_bindings(&self, action: &str) -> &[Binding] {
+        return self.bindings.get(action).map_or(&[], |b| b.as_slice());
+    }
+
+    /// Remove an action along with its bindings.
+    pub fn remove_action(&mut self, action: &str) {
+        self.bindings.remove(action);
+        self._states.remove(action);
+    }
+
+    /// Update the state of every action from the input state of this frame.
+    pub fn update(&mut self, input: &InputState) {
+        for (action, bindings) in &self.bindings {
+            let value: f32 = bindings.iter().map(|b| b.get_value(input)).sum();
+            let held = value.abs() >= HELD_THRESHOLD;
+            let state = self._states.entry(action.clone()).or_default();
+            state.pressed = held && !state.held;
+            state.released = !held && state.held;
+            state.held = held;
+            state.value = value;
+        }
+    }
+
+    /// Get whether the action started being held this frame.
+    pub fn action_pressed(&self, action: &str) -> bool {
+        return self._states.get(action).is_some_and(|s| s.pressed);
+    }
+
+    /// Get whether the action stopped being held this frame.
+    pub fn action_released(&self, action: &str) -> bool {
+        return self._states.get(action).is_some_and(|s| s.released);
+    }
+
+    /// Get whether the action is held.
+    pub fn action_held(&self, action: &str) -> bool {
+        return self._states.get(action).is_some_and(|s| s.held);
+    }
+
+    /// Get the analog value of the action.
+    pub fn action_value(&self, action: &str) -> f32 {
+        return self._states.get(action).map_or(0.0, |s| s.value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Action, Event};
+
+    fn press(input: &mut InputState, key: Key) {
+        input.update(&Event::Key {
+            key,
+            scancode: 0,
+            action: Action::Press,
+            modifiers: Modifiers::default(),
+        });
+    }
+
+    fn release(input: &mut InputState, key: Key) {
+        input.update(&Event::Key {
+            key,
+            scancode: 0,
+            action: Action::Release,
+            modifiers: Modifiers::default(),
+        });
+    }
+
+    fn control() -> Modifiers {
+        return Modifiers {
+            control: true,
+            ..Default::default()
+        };
+    }
+
+    #[test]
+    fn action_edges_follow_the_binding() {
+        let mut map = ActionMap::new().bind("jump", Binding::new(InputSource::Key(Key::Space)));
+        let mut input = InputState::new();
+
+        press(&mut input, Key::Space);
+        map.update(&input);
+        assert!(map.action_pressed("jump"));
+        assert!(map.action_held("jump"));
+
+        input.begin_frame();
+        map.update(&input);
+        assert!(!map.action_pressed("jump"));
+        assert!(map.action_held("jump"));
+
+        input.begin_frame();
+        release(&mut input, Key::Space);
+        map.update(&input);
+        assert!(map.action_released("jump"));
+        assert!(!map.action_held("jump"));
+    }
+
+    #[test]
+    fn chord_needs_every_input() {
+        let mut map = ActionMap::new().bind(
+            "orbit",
+            Binding::chord(&[
+                InputSource::MouseButton(MouseButton::Left),
+                InputSource::Key(Key::O),
+            ]),
+        );
+        let mut input = InputState::new();
+
+        press(&mut input, Key::O);
+        map.update(&input);
+        assert!(!map.action_held("orbit"));
+
+        input.update(&Event::MouseButton {
+            button: MouseButton::Left,
+            action: Action::Press,
+            modifiers: Modifiers::default(),
+        });
+        map.update(&input);
+        assert!(map.action_held("orbit"));
+    }
+
+    #[test]
+    fn required_modifiers_must_be_held() {
+        let mut map = ActionMap::new().bind(
+            "save",
+            Binding::new(InputSource::Key(Key::S)).set_modifiers(control()),
+        );
+        let mut input = InputState::new();
+
+        press(&mut input, Key::S);
+        map.update(&input);
+        assert!(!map.action_held("save"));
+
+        press(&mut input, Key::RightControl);
+        map.update(&input);
+        assert!(map.action_held("save"));
+    }
+
+    #[test]
+    fn modifiers_must_match_exactly() {
+        let mut map = ActionMap::new()
+            .bind("forward", Binding::new(InputSource::Key(Key::S)))
+            .bind(
+                "save",
+                Binding::new(InputSource::Key(Key::S)).set_modifiers(control()),
+            );
+        let mut input = InputState::new();
+
+        press(&mut input, Key::LeftControl);
+        press(&mut input, Key::S);
+        map.update(&input);
+        assert!(map.action_held("save"));
+        assert!(!map.action_held("forward"));
+
+        press(&mut input, Key::LeftShift);
+        map.update(&input);
+        assert!(!map.action_held("save"));
+    }
+
+    #[test]
+    fn lock_keys_are_ignored() {
+        let mut map = ActionMap::new().bind("forward", Binding::new(InputSource::Key(Key::W)));
+        let mut input = InputState::new();
+
+        press(&mut input, Key::CapsLock);
+        press(&mut input, Key::NumLock);
+        press(&mut input, Key::W);
+        map.update(&input);
+        assert!(map.action_held("forward"));
+    }
+
+    #[test]
+    fn any_modifiers_allows_extra_modifiers() {
+        let mut map = ActionMap::new().bind(
+            "forward",
+            Binding::new(InputSource::Key(Key::W)).set_any_modifiers(true),
+        );
+        let mut input = InputState::new();
+
+        press(&mut input, Key::LeftShift);
+        press(&mut input, Key::W);
+        map.update(&input);
+        assert!(map.action_held("forward"));
+    }
+
+    #[test]
+    fn modifier_keys_can_be_inputs() {
+        let mut map = ActionMap::new()
+            .bind("crouch", Binding::new(InputSource::Key(Key::LeftControl)))
+            .bind(
+                "sprint",
+                Binding::chord(&[InputSource::Key(Key::LeftShift), InputSource::Key(Key::W)]),
+            );
+        let mut input = InputState::new();
+
+        press(&mut input, Key::LeftControl);
+        map.update(&input);
+        assert!(map.action_held("crouch"));
+
+        release(&mut input, Key::LeftControl);
+        press(&mut input, Key::LeftShift);
+        press(&mut input, Key::W);
+        map.update(&input);
+        assert!(map.action_held("sprint"));
+    }
+
+    #[test]
+    fn scaled_bindings_are_summed() {
+        let mut map = ActionMap::new()
+            .bind("move", Binding::new(InputSource::Key(Key::D)))
+            .bind(
+                "move",
+                Binding::new(InputSource::Key(Key::A)).set_scale(-1.0),
+            );
+        let mut input = InputState::new();
+
+        press(&mut input, Key::A);
+        map.update(&input);
+        assert_eq!(map.action_value("move"), -1.0);
+        assert!(map.action_held("move"));
+
+        press(&mut input, Key::D);
+        map.update(&input);
+        assert_eq!(map.action_value("move"), 0.0);
+        assert!(!map.action_held("move"));
+    }
+
+    #[test]
+    fn small_analog_values_are_not_held() {
+        let mut map = ActionMap::new().bind(
+            "throttle",
+            Binding::new(InputSource::GamepadAxis(GamepadAxis::RightTrigger)),
+        );
+        let mut input = InputState::new();
+
+        input.update(&Event::GamepadAxis {
+            id: 0,
+            axis: GamepadAxis::RightTrigger,
+            value: 0.25,
+        });
+        map.update(&input);
+        assert_eq!(map.action_value("throttle"), 0.25);
+        assert!(!map.action_held("throttle"));
+    }
+}
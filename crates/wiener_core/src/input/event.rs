@@ -0,0 +1,96 @@
+use crate::{Action, GamepadAxis, GamepadButton, Key, Modifiers, MouseButton};
+
+use glfw;
+use std::path::PathBuf;
+
+/// Event polled from a window, independent of the windowing backend.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Event {
+    /// A key was pressed, released or repeated.
+    Key {
+        key: Key,
+        scancode: i32,
+        action: Action,
+        modifiers: Modifiers,
+    },
+
+    /// A mouse button was pressed or released.
+    MouseButton {
+        button: MouseButton,
+        action: Action,
+        modifiers: Modifiers,
+    },
+
+    /// The cursor moved to the given position, in screen coordinates
+    /// relative to the top-left corner of the window.
+    CursorMoved { x: f64, y: f64 },
+
+    /// The cursor entered (`true`) or left (`false`) the window.
+    CursorEntered(bool),
+
+    /// The mouse wheel or touchpad was scrolled.
+    Scroll { x: f64, y: f64 },
+
+    /// The framebuffer was resized, with the new size in pixels.
+    Resized { width: i32, height: i32 },
+
+    /// The window gained (`true`) or lost (`false`) focus.
+    Focused(bool),
+
+    /// Files were dropped onto the window.
+    FileDrop(Vec<PathBuf>),
+
+    /// A gamepad with the given id was connected.
+    GamepadConnected(u32),
+
+    /// The gamepad with the given id was disconnected.
+    GamepadDisconnected(u32),
+
+    /// A gamepad button was pressed or released.
+    GamepadButton {
+        id: u32,
+        button: GamepadButton,
+        action: Action,
+    },
+
+    /// A gamepad axis changed its value, with the deadzone already applied.
+    GamepadAxis {
+        id: u32,
+        axis: GamepadAxis,
+        value: f32,
+    },
+
+    /// The user asked to close the window.
+    Close,
+}
+
+impl Event {
+    /// Convert a GLFW event, returning `None` for events that have no
+    /// equivalent.
+    pub fn from_glfw(event: glfw::WindowEvent) -> Option<Self> {
+        return match event {
+            glfw::WindowEvent::Key(key, scancode, action, modifiers) => Some(Event::Key {
+                key: key.into(),
+                scancode,
+                action: action.into(),
+                modifiers: modifiers.into(),
+            }),
+            glfw::WindowEvent::MouseButton(button, action, modifiers) => Some(Event::MouseButton {
+                button: button.into(),
+                action: action.into(),
+                modifiers: modifiers.into(),
+            }),
+            glfw::WindowEvent::CursorPos(x, y) => Some(Event::CursorMoved { x, y }),
+            glfw::WindowEvent::CursorEnter(entered) => Some(Event::CursorEntered(entered)),
+            glfw::WindowEvent::Scroll(x, y) => Some(Event::Scroll { x, y }),
+            glfw::WindowEvent::FramebufferSize(width, height) => {
+                Some(Event::Resized { width, height })
+            }
+            glfw::WindowEvent::Focus(focused) => Some(Event::Focused(focused)),
+            glfw::WindowEvent::FileDrop(paths) => Some(Event::FileDrop(paths)),
+            glfw::WindowEvent::Close => Some(Event::Close),
+            _ => None,
+        };
+    }
+}
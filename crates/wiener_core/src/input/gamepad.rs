@@ -0,0 +1,432 @@
+use crate::{Action, Event};
+
+use glfw;
+use log;
+
+/// Number of joysticks supported by GLFW.
+const MAX_JOYSTICKS: u32 = 16;
+
+/// Largest stick deadzone, which must stay below 1 so the rest of the range
+/// can be rescaled.
+const MAX_DEADZONE: f32 = 0.99;
+
+/// Gamepad button, using the Xbox controller layout.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GamepadButton {
+    A,
+    B,
+    X,
+    Y,
+    LeftBumper,
+    RightBumper,
+    Back,
+    Start,
+    Guide,
+    LeftThumb,
+    RightThumb,
+    DpadUp,
+    DpadRight,
+    DpadDown,
+    DpadLeft,
+}
+
+impl GamepadButton {
+    /// Every gamepad button.
+    pub const ALL: [GamepadButton; 15] = [
+        GamepadButton::A,
+        GamepadButton::B,
+        GamepadButton::X,
+        GamepadButton::Y,
+        GamepadButton::LeftBumper,
+        GamepadButton::RightBumper,
+        GamepadButton::Back,
+        GamepadButton::Start,
+        GamepadButton::Guide,
+        GamepadButton::LeftThumb,
+        GamepadButton::RightThumb,
+        GamepadButton::DpadUp,
+        GamepadButton::DpadRight,
+        GamepadButton::DpadDown,
+        GamepadButton::DpadLeft,
+    ];
+}
+
+impl From<GamepadButton> for glfw::GamepadButton {
+    fn from(button: GamepadButton) -> Self {
+        return match button {
+            GamepadButton::A => glfw::GamepadButton::ButtonA,
+            GamepadButton::B => glfw::GamepadButton::ButtonB,
+            GamepadButton::X => glfw::GamepadButton::ButtonX,
+            GamepadButton::Y => glfw::GamepadButton::ButtonY,
+            GamepadButton::LeftBumper => glfw::GamepadButton::ButtonLeftBumper,
+            GamepadButton::RightBumper => glfw::GamepadButton::ButtonRightBumper,
+            GamepadButton::Back => glfw::GamepadButton::ButtonBack,
+            GamepadButton::Start => glfw::GamepadButton::ButtonStart,
+            GamepadButton::Guide => glfw::GamepadButton::ButtonGuide,
+            GamepadButton::LeftThumb => glfw::GamepadButton::ButtonLeftThumb,
+            GamepadButton::RightThumb => glfw::GamepadButton::ButtonRightThumb,
+            GamepadButton::DpadUp => glfw::GamepadButton::ButtonDpadUp,
+            GamepadButton::DpadRight => glfw::GamepadButton::ButtonDpadRight,
+            GamepadButton::DpadDown => glfw::GamepadButton::ButtonDpadDown,
+            GamepadButton::DpadLeft => glfw::GamepadButton::ButtonDpadLeft,
+        };
+    }
+}
+
+/// Gamepad axis. Stick axes go from -1 to 1, and triggers from 0 (released)
+/// to 1 (fully pressed).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GamepadAxis {
+    LeftX,
+    LeftY,
+    RightX,
+    RightY,
+    LeftTrigger,
+    RightTrigger,
+}
+
+impl GamepadAxis {
+    /// Every gamepad axis.
+    pub const ALL: [GamepadAxis; 6] = [
+        GamepadAxis::LeftX,
+        GamepadAxis::LeftY,
+        GamepadAxis::RightX,
+        GamepadAxis::RightY,
+        GamepadAxis::LeftTrigger,
+        GamepadAxis::RightTrigger,
+    ];
+}
+
+impl From<GamepadAxis> for glfw::GamepadAxis {
+    fn from(axis: GamepadAxis) -> Self {
+        return match axis {
+            GamepadAxis::LeftX => glfw::GamepadAxis::AxisLeftX,
+            GamepadAxis::LeftY => glfw::GamepadAxis::AxisLeftY,
+            GamepadAxis::RightX => glfw::GamepadAxis::AxisRightX,
+            GamepadAxis::RightY => glfw::GamepadAxis::AxisRightY,
+            GamepadAxis::LeftTrigger => glfw::GamepadAxis::AxisLeftTrigger,
+            GamepadAxis::RightTrigger => glfw::GamepadAxis::AxisRightTrigger,
+        };
+    }
+}
+
+/// Information about a connected joystick.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GamepadInfo {
+    /// Index of the joystick, from 0 to 15.
+    pub id: u32,
+
+    /// Name of the joystick, or the name of its gamepad mapping if it has
+    /// one.
+    pub name: String,
+
+    /// Whether the joystick has a gamepad mapping or not. Only joysticks
+    /// with a mapping produce gamepad events.
+    pub is_gamepad: bool,
+}
+
+/// State of the buttons and axes of a gamepad.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct GamepadState {
+    _buttons: [bool; 15],
+    _axes: [f32; 6],
+}
+
+impl GamepadState {
+    /// Read the state of a GLFW gamepad, applying a radial deadzone to the
+    /// sticks.
+    fn from_glfw(state: &glfw::GamepadState, deadzone: f32) -> Self {
+        let mut buttons = [false; 15];
+        for (i, button) in GamepadButton::ALL.iter().enumerate() {
+            buttons[i] = state.get_button_state((*button).into()) != glfw::Action::Release;
+        }
+        let axis = |a: GamepadAxis| state.get_axis(a.into());
+        let (left_x, left_y) =
+            apply_deadzone(axis(GamepadAxis::LeftX), axis(GamepadAxis::LeftY), deadzone);
+        let (right_x, right_y) = apply_deadzone(
+            axis(GamepadAxis::RightX),
+            axis(GamepadAxis::RightY),
+            deadzone,
+        );
+        return GamepadState {
+            _buttons: buttons,
+            _axes: [
+                left_x,
+                left_y,
+                right_x,
+                right_y,
+                (axis(GamepadAxis::LeftTrigger) + 1.0) / 2.0,
+                (axis(GamepadAxis::RightTrigger) + 1.0) / 2.0,
+            ],
+        };
+    }
+
+    /// Get whether a button is held down.
+    pub fn is_button_held(&self, button: GamepadButton) -> bool {
+        return self._buttons[button as usize];
+    }
+
+    /// Get the value of an axis.
+    pub fn get_axis(&self, axis: GamepadAxis) -> f32 {
+        return self._axes[axis as usize];
+    }
+
+    /// Set whether a button is held down.
+    pub fn set_button(&mut self, button: GamepadButton, held: bool) {
+        self._buttons[button as usize] = held;
+    }
+
+    /// Set the value of an axis.
+    pub fn set_axis(&mut self, axis: GamepadAxis, value: f32) {
+        self._axes[axis as usize] = value;
+    }
+}
+
+/// Apply a radial deadzone to a stick, rescaling the rest of the range so
+/// the output still goes smoothly from 0 to 1.
+fn apply_deadzone(x: f32, y: f32, deadzone: f32) -> (f32, f32) {
+    let magnitude = (x * x + y * y).sqrt();
+    if magnitude <= deadzone {
+        return (0.0, 0.0);
+    }
+    let scale = ((magnitude - deadzone) / (1.0 - deadzone)).min(1.0) / magnitude;
+    return (x * scale, y * scale);
+}
+
+/// Clamp a stick deadzone to [0, `MAX_DEADZONE`], warning if it was out of
+/// range. Invalid values (e.g. NaN) disable the deadzone.
+fn clamp_deadzone(deadzone: f32) -> f32 {
+    if (0.0..=MAX_DEADZONE).contains(&deadzone) {
+        return deadzone;
+    }
+    let clamped = if deadzone.is_nan() {
+        0.0
+    } else {
+        deadzone.clamp(0.0, MAX_DEADZONE)
+    };
+    log::warn!(
+        "GamepadPoller :: Deadzone {:?} is out of range, using {:?}",
+        deadzone,
+        clamped
+    );
+    return clamped;
+}
+
+/// Get the information of every connected joystick.
+pub fn get_gamepads(glfw_inst: &glfw::Glfw) -> Vec<GamepadInfo> {
+    let mut gamepads = Vec::new();
+    for id in 0..MAX_JOYSTICKS {
+        let joystick = glfw_inst.get_joystick(joystick_id(id));
+        if !joystick.is_present() {
+            continue;
+        }
+        let is_gamepad = joystick.is_gamepad();
+        let name = if is_gamepad {
+            joystick.get_gamepad_name()
+        } else {
+            joystick.get_name()
+        };
+        gamepads.push(GamepadInfo {
+            id,
+            name: name.unwrap_or_default(),
+            is_gamepad,
+        });
+    }
+    return gamepads;
+}
+
+/// Add SDL style gamepad mappings (i.e. lines of `gamecontrollerdb.txt`),
+/// returning whether they could be parsed or not.
+pub fn update_gamepad_mappings(glfw_inst: &glfw::Glfw, mappings: &str) -> bool {
+    log::info!("update_gamepad_mappings :: Updating gamepad mappings");
+    return glfw_inst.update_gamepad_mappings(mappings);
+}
+
+/// Convert an index into a GLFW joystick id.
+fn joystick_id(id: u32) -> glfw::JoystickId {
+    return glfw::JoystickId::from_i32(id as i32).expect("Invalid joystick id");
+}
+
+/// Poller that turns the state of the gamepads into events. GLFW does not
+/// report gamepad input as window events, so the state is compared with the
+/// one of the previous poll instead.
+#[derive(Clone, Debug)]
+pub struct GamepadPoller {
+    _states: [Option<GamepadState>; MAX_JOYSTICKS as usize],
+    _deadzone: f32,
+}
+
+impl GamepadPoller {
+    /// Create a poller with the given stick deadzone, which is clamped to
+    /// [0, 0.99].
+    pub fn new(deadzone: f32) -> Self {
+        return GamepadPoller {
+            _states: [None; MAX_JOYSTICKS as usize],
+            _deadzone: clamp_deadzone(deadzone),
+        };
+    }
+
+    /// Get the stick deadzone.
+    pub fn get_deadzone(&self) -> f32 {
+        return self._deadzone;
+    }
+
+    /// Set the stick deadzone, which is clamped to [0, 0.99].
+    pub fn set_deadzone(&mut self, deadzone: f32) {
+        self._deadzone = clamp_deadzone(deadzone);
+    }
+
+    /// Read the gamepads, returning the events that happened since the last
+    /// poll.
+    pub fn poll(&mut self, glfw_inst: &glfw::Glfw) -> Vec<Event> {
+        let mut events = Vec::new();
+        for id in 0..MAX_JOYSTICKS {
+            let joystick = glfw_inst.get_joystick(joystick_id(id));
+            let current = if joystick.is_gamepad() {
+                joystick
+                    .get_gamepad_state()
+                    .map(|s| GamepadState::from_glfw(&s, self._deadzone))
+            } else {
+                None
+            };
+            let previous = self._states[id as usize];
+            match (previous, current) {
+                (None, Some(state)) => {
+                    log::info!("GamepadPoller :: Gamepad {:?} connected", id);
+                    events.push(Event::GamepadConnected(id));
+                    diff_states(id, &GamepadState::default(), &state, &mut events);
+                }
+                (Some(_), None) => {
+                    log::info!("GamepadPoller :: Gamepad {:?} disconnected", id);
+                    events.push(Event::GamepadDisconnected(id));
+                }
+                (Some(old), Some(new)) => diff_states(id, &old, &new, &mut events),
+                (None, None) => {}
+            }
+            self._states[id as usize] = current;
+        }
+        return events;
+    }
+}
+
+/// Push the events that turn one gamepad state into another.
+fn diff_states(id: u32, old: &GamepadState, new: &GamepadState, events: &mut Vec<Event>) {
+    for button in GamepadButton::ALL {
+        let held = new.is_button_held(button);
+        if held != old.is_button_held(button) {
+            events.push(Event::GamepadButton {
+                id,
+                button,
+                action: if held { Action::Press } else { Action::Release },
+            });
+        }
+    }
+    for axis in GamepadAxis::ALL {
+        let value = new.get_axis(axis);
+        if value != old.get_axis(axis) {
+            events.push(Event::GamepadAxis { id, axis, value });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: (f32, f32), expected: (f32, f32)) {
+        assert!(
+            (actual.0 - expected.0).abs() < 1e-5 && (actual.1 - expected.1).abs() < 1e-5,
+            "{:?} != {:?}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn deadzone_zeroes_small_inputs() {
+        assert_eq!(apply_deadzone(0.1, 0.1, 0.2), (0.0, 0.0));
+        assert_eq!(apply_deadzone(0.0, -0.2, 0.2), (0.0, 0.0));
+    }
+
+    #[test]
+    fn deadzone_is_radial() {
+        // Each axis is inside the deadzone, but the stick as a whole is not
+        let (x, y) = apply_deadzone(0.15, 0.15, 0.2);
+        assert!(x > 0.0 && y > 0.0);
+        assert!((x - y).abs() < 1e-6);
+    }
+
+    #[test]
+    fn deadzone_rescales_the_remaining_range() {
+        assert_close(apply_deadzone(0.6, 0.0, 0.2), (0.5, 0.0));
+        assert_close(apply_deadzone(0.0, -1.0, 0.2), (0.0, -1.0));
+        assert_close(apply_deadzone(3.0, 4.0, 0.2), (0.6, 0.8));
+    }
+
+    #[test]
+    fn deadzone_of_one_is_clamped() {
+        let poller = GamepadPoller::new(1.0);
+        assert_eq!(poller.get_deadzone(), MAX_DEADZONE);
+        assert_eq!(apply_deadzone(0.5, 0.5, poller.get_deadzone()), (0.0, 0.0));
+        assert_close(apply_deadzone(0.0, 1.0, poller.get_deadzone()), (0.0, 1.0));
+        assert_close(apply_deadzone(3.0, 4.0, poller.get_deadzone()), (0.6, 0.8));
+    }
+
+    #[test]
+    fn negative_deadzone_is_clamped() {
+        let mut poller = GamepadPoller::new(0.2);
+        poller.set_deadzone(-0.5);
+        assert_eq!(poller.get_deadzone(), 0.0);
+        assert_close(
+            apply_deadzone(0.3, -0.4, poller.get_deadzone()),
+            (0.3, -0.4),
+        );
+        assert_close(apply_deadzone(1.0, 0.0, poller.get_deadzone()), (1.0, 0.0));
+        poller.set_deadzone(f32::NAN);
+        assert_eq!(poller.get_deadzone(), 0.0);
+    }
+
+    #[test]
+    fn diff_of_equal_states_is_empty() {
+        let mut state = GamepadState::default();
+        state.set_button(GamepadButton::A, true);
+        state.set_axis(GamepadAxis::LeftX, 0.5);
+        let mut events = Vec::new();
+        diff_states(0, &state, &state, &mut events);
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn diff_reports_buttons_then_axes() {
+        let mut old = GamepadState::default();
+        old.set_button(GamepadButton::B, true);
+        let mut new = GamepadState::default();
+        new.set_button(GamepadButton::A, true);
+        new.set_axis(GamepadAxis::RightTrigger, 1.0);
+
+        let mut events = Vec::new();
+        diff_states(2, &old, &new, &mut events);
+        assert_eq!(
+            events,
+            vec![
+                Event::GamepadButton {
+                    id: 2,
+                    button: GamepadButton::A,
+                    action: Action::Press,
+                },
+                Event::GamepadButton {
+                    id: 2,
+                    button: GamepadButton::B,
+                    action: Action::Release,
+                },
+                Event::GamepadAxis {
+                    id: 2,
+                    axis: GamepadAxis::RightTrigger,
+                    value: 1.0,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,228 @@
+use glfw;
+
+/// Define the keyboard keys along with the conversions from and to GLFW keys,
+/// which use the same variant names.
+macro_rules! define_keys {
+    ($($name:ident),* $(,)?) => {
+        /// Keyboard key, independent of the windowing backend.
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        pub enum Key {
+            $($name,)*
+        }
+
+        impl From<glfw::Key> for Key {
+            fn from(key: glfw::Key) -> Self {
+                return match key {
+                    $(glfw::Key::$name => Key::$name,)*
+                };
+            }
+        }
+
+        impl From<Key> for glfw::Key {
+            fn from(key: Key) -> Self {
+                return match key {
+                    $(Key::$name => glfw::Key::$name,)*
+                };
+            }
+        }
+    };
+}
+
+define_keys!(
+    Space,
+    Apostrophe,
+    Comma,
+    Minus,
+    Period,
+    Slash,
+    Num0,
+    Num1,
+    Num2,
+    Num3,
+    Num4,
+    Num5,
+    Num6,
+    Num7,
+    Num8,
+    Num9,
+    Semicolon,
+    Equal,
+    A,
+    B,
+    C,
+    D,
+    E,
+    F,
+    G,
+    H,
+    I,
+    J,
+    K,
+    L,
+    M,
+    N,
+    O,
+    P,
+    Q,
+    R,
+    S,
+    T,
+    U,
+    V,
+    W,
+    X,
+    Y,
+    Z,
+    LeftBracket,
+    Backslash,
+    RightBracket,
+    GraveAccent,
+    World1,
+    World2,
+    Escape,
+    Enter,
+    Tab,
+    Backspace,
+    Insert,
+    Delete,
+    Right,
+    Left,
+    Down,
+    Up,
+    PageUp,
+    PageDown,
+    Home,
+    End,
+    CapsLock,
+    ScrollLock,
+    NumLock,
+    PrintScreen,
+    Pause,
+    F1,
+    F2,
+    F3,
+    F4,
+    F5,
+    F6,
+    F7,
+    F8,
+    F9,
+    F10,
+    F11,
+    F12,
+    F13,
+    F14,
+    F15,
+    F16,
+    F17,
+    F18,
+    F19,
+    F20,
+    F21,
+    F22,
+    F23,
+    F24,
+    F25,
+    Kp0,
+    Kp1,
+    Kp2,
+    Kp3,
+    Kp4,
+    Kp5,
+    Kp6,
+    Kp7,
+    Kp8,
+    Kp9,
+    KpDecimal,
+    KpDivide,
+    KpMultiply,
+    KpSubtract,
+    KpAdd,
+    KpEnter,
+    KpEqual,
+    LeftShift,
+    LeftControl,
+    LeftAlt,
+    LeftSuper,
+    RightShift,
+    RightControl,
+    RightAlt,
+    RightSuper,
+    Menu,
+    Unknown,
+);
+
+/// Mouse button, independent of the windowing backend.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MouseButton {
+    Left,
+    Right,
+    Middle,
+    Button4,
+    Button5,
+    Button6,
+    Button7,
+    Button8,
+}
+
+impl From<glfw::MouseButton> for MouseButton {
+    fn from(button: glfw::MouseButton) -> Self {
+        return match button {
+            glfw::MouseButton::Button1 => MouseButton::Left,
+            glfw::MouseButton::Button2 => MouseButton::Right,
+            glfw::MouseButton::Button3 => MouseButton::Middle,
+            glfw::MouseButton::Button4 => MouseButton::Button4,
+            glfw::MouseButton::Button5 => MouseButton::Button5,
+            glfw::MouseButton::Button6 => MouseButton::Button6,
+            glfw::MouseButton::Button7 => MouseButton::Button7,
+            glfw::MouseButton::Button8 => MouseButton::Button8,
+        };
+    }
+}
+
+/// Action that happened to a key or button.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Action {
+    Press,
+    Release,
+    Repeat,
+}
+
+impl From<glfw::Action> for Action {
+    fn from(action: glfw::Action) -> Self {
+        return match action {
+            glfw::Action::Press => Action::Press,
+            glfw::Action::Release => Action::Release,
+            glfw::Action::Repeat => Action::Repeat,
+        };
+    }
+}
+
+/// Modifier keys that were held when an event happened.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Modifiers {
+    pub shift: bool,
+    pub control: bool,
+    pub alt: bool,
+    pub super_key: bool,
+    pub caps_lock: bool,
+    pub num_lock: bool,
+}
+
+impl From<glfw::Modifiers> for Modifiers {
+    fn from(modifiers: glfw::Modifiers) -> Self {
+        return Modifiers {
+            shift: modifiers.contains(glfw::Modifiers::Shift),
+            control: modifiers.contains(glfw::Modifiers::Control),
+            alt: modifiers.contains(glfw::Modifiers::Alt),
+            super_key: modifiers.contains(glfw::Modifiers::Super),
+            caps_lock: modifiers.contains(glfw::Modifiers::CapsLock),
+            num_lock: modifiers.contains(glfw::Modifiers::NumLock),
+        };
+    }
+}
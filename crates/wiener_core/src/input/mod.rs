@@ -0,0 +1,13 @@
+mod action_map;
+mod event;
+mod gamepad;
+mod key;
+mod recording;
+mod state;
+
+pub use action_map::*;
+pub use event::*;
+pub use gamepad::*;
+pub use key::*;
+pub use recording::*;
+pub use state::*;
@@ -0,0 +1,169 @@
+use crate::Event;
+
+/// Events received by a single poll, along with the time of the poll.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RecordedFrame {
+    /// Time of the poll, in seconds since the window was created.
+    pub time: f64,
+
+    /// Events received by the poll.
+    pub events: Vec<Event>,
+}
+
+/// Recording of the events received by a window, one frame per poll.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct InputRecording {
+    pub frames: Vec<RecordedFrame>,
+}
+
+impl InputRecording {
+    /// Create an empty recording.
+    pub fn new() -> Self {
+        return InputRecording::default();
+    }
+
+    /// Add a frame at the end of the recording.
+    pub fn push_frame(&mut self, time: f64, events: &[Event]) {
+        self.frames.push(RecordedFrame {
+            time,
+            events: events.to_vec(),
+        });
+    }
+
+    /// Get the duration of the recording, in seconds.
+    pub fn get_duration(&self) -> f64 {
+        return match (self.frames.first(), self.frames.last()) {
+            (Some(first), Some(last)) => last.time - first.time,
+            _ => 0.0,
+        };
+    }
+}
+
+/// Player that hands out the frames of a recording in order.
+///
+/// The recorded times are relative to whenever the recording was made, so
+/// the player shifts them by an offset (see `start_at`) to keep the clock of
+/// the replaying window going forward.
+#[derive(Clone, Debug)]
+pub struct InputReplay {
+    _recording: InputRecording,
+    _next_frame: usize,
+    _offset: f64,
+}
+
+impl InputReplay {
+    /// Create a player that starts at the first frame of the recording, with
+    /// the times as recorded.
+    pub fn new(recording: InputRecording) -> Self {
+        return InputReplay {
+            _recording: recording,
+            _next_frame: 0,
+            _offset: 0.0,
+        };
+    }
+
+    /// Shift the times of the frames so the first one happens at `time`.
+    pub fn start_at(mut self, time: f64) -> Self {
+        self._offset = match self._recording.frames.first() {
+            Some(first) => time - first.time,
+            None => 0.0,
+        };
+        return self;
+    }
+
+    /// Get the offset added to the recorded times.
+    pub fn get_offset(&self) -> f64 {
+        return self._offset;
+    }
+
+    /// Get the time of a frame of the recording, shifted by the offset.
+    pub fn get_time(&self, frame: &RecordedFrame) -> f64 {
+        return frame.time + self._offset;
+    }
+
+    /// Get the next frame, or `None` if the replay is over.
+    pub fn next_frame(&mut self) -> Option<&RecordedFrame> {
+        let frame = self._recording.frames.get(self._next_frame);
+        if frame.is_some() {
+            self._next_frame += 1;
+        }
+        return frame;
+    }
+
+    /// Get whether every frame has been handed out or not.
+    pub fn is_finished(&self) -> bool {
+        return self._next_frame >= self._recording.frames.len();
+    }
+
+    /// Get the recording being replayed.
+    pub fn get_recording(&self) -> &InputRecording {
+        return &self._recording;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recording() -> InputRecording {
+        let mut recording = InputRecording::new();
+        recording.push_frame(1.0, &[Event::Focused(true)]);
+        recording.push_frame(1.5, &[]);
+        recording.push_frame(2.25, &[Event::Scroll { x: 0.0, y: 1.0 }, Event::Close]);
+        return recording;
+    }
+
+    #[test]
+    fn duration_spans_first_to_last_frame() {
+        assert_eq!(recording().get_duration(), 1.25);
+        assert_eq!(InputRecording::new().get_duration(), 0.0);
+    }
+
+    #[test]
+    fn replay_steps_through_every_frame_in_order() {
+        let mut replay = InputReplay::new(recording());
+        assert!(!replay.is_finished());
+
+        let frame = replay.next_frame().unwrap();
+        assert_eq!(frame.time, 1.0);
+        assert_eq!(frame.events, vec![Event::Focused(true)]);
+
+        let frame = replay.next_frame().unwrap();
+        assert_eq!(frame.time, 1.5);
+        assert!(frame.events.is_empty());
+        assert!(!replay.is_finished());
+
+        let frame = replay.next_frame().unwrap();
+        assert_eq!(frame.time, 2.25);
+        assert_eq!(frame.events.len(), 2);
+        assert!(replay.is_finished());
+
+        assert!(replay.next_frame().is_none());
+        assert!(replay.next_frame().is_none());
+        assert!(replay.is_finished());
+    }
+
+    #[test]
+    fn replay_shifts_the_recorded_times() {
+        let mut replay = InputReplay::new(recording()).start_at(100.0);
+        assert_eq!(replay.get_offset(), 99.0);
+        let mut times = Vec::new();
+        while let Some(frame) = replay.next_frame().cloned() {
+            times.push(replay.get_time(&frame));
+        }
+        assert_eq!(times, vec![100.0, 100.5, 101.25]);
+
+        let replay = InputReplay::new(InputRecording::new()).start_at(100.0);
+        assert_eq!(replay.get_offset(), 0.0);
+    }
+
+    #[test]
+    fn empty_replay_is_finished() {
+        let mut replay = InputReplay::new(InputRecording::new());
+        assert!(replay.is_finished());
+        assert!(replay.next_frame().is_none());
+    }
+}
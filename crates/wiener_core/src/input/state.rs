@@ -0,0 +1,388 @@
+use crate::{Action, Event, GamepadAxis, GamepadButton, GamepadState, Key, Modifiers, MouseButton};
+
+use std::collections::{HashMap, HashSet};
+
+/// State of the keyboard and mouse, updated from polled events.
+///
+/// Queries about presses and releases refer to the current frame, which
+/// starts every time `begin_frame` is called.
+#[derive(Clone, Debug, Default)]
+pub struct InputState {
+    _keys_held: HashSet<Key>,
+    _keys_pressed: HashSet<Key>,
+    _keys_released: HashSet<Key>,
+    _buttons_held: HashSet<MouseButton>,
+    _buttons_pressed: HashSet<MouseButton>,
+    _buttons_released: HashSet<MouseButton>,
+    _modifiers: Modifiers,
+    _cursor_position: Option<(f64, f64)>,
+    _cursor_inside: bool,
+    _mouse_delta: (f64, f64),
+    _scroll_delta: (f64, f64),
+    _focused: bool,
+    _gamepads: HashMap<u32, GamepadState>,
+    _gamepad_buttons_pressed: HashSet<(u32, GamepadButton)>,
+    _gamepad_buttons_released: HashSet<(u32, GamepadButton)>,
+}
+
+impl InputState {
+    /// Create an empty input state.
+    pub fn new() -> Self {
+        return InputState::default();
+    }
+
+    /// Start a new frame, clearing the presses, releases and deltas of the
+    /// previous one.
+    pub fn begin_frame(&mut self) {
+        self._keys_pressed.clear();
+        self._keys_released.clear();
+        self._buttons_pressed.clear();
+        self._buttons_released.clear();
+        self._mouse_delta = (0.0, 0.0);
+        self._scroll_delta = (0.0, 0.0);
+        self._gamepad_buttons_pressed.clear();
+        self._gamepad_buttons_released.clear();
+    }
+
+    /// Update the state with an event.
+    pub fn update(&mut self, event: &Event) {
+        match event {
+            Event::Key {
+                key,
+                action,
+                modifiers,
+                ..
+            } => {
+                self._modifiers = *modifiers;
+                match action {
+                    Action::Press => {
+                        self._keys_held.insert(*key);
+                        self._keys_pressed.insert(*key);
+                    }
+                    Action::Release => {
+                        self._keys_held.remove(key);
+                        self._keys_released.insert(*key);
+                    }
+                    Action::Repeat => {}
+                }
+            }
+            Event::MouseButton {
+                button,
+                action,
+                modifiers,
+            } => {
+                self._modifiers = *modifiers;
+                match action {
+                    Action::Press => {
+                        self._buttons_held.insert(*button);
+                        self._buttons_pressed.insert(*button);
+                    }
+                    Action::Release => {
+                        self._buttons_held.remove(button);
+                        self._buttons_released.insert(*button);
+                    }
+                    Action::Repeat => {}
+                }
+            }
+            Event::CursorMoved { x, y } => {
+                if let Some((last_x, last_y)) = self._cursor_position {
+                    self._mouse_delta.0 += x - last_x;
+                    self._mouse_delta.1 += y - last_y;
+                }
+                self._cursor_position = Some((*x, *y));
+            }
+            Event::CursorEntered(entered) => self._cursor_inside = *entered,
+            Event::Scroll { x, y } => {
+                self._scroll_delta.0 += x;
+                self._scroll_delta.1 += y;
+            }
+            Event::Focused(focused) => {
+                self._focused = *focused;
+                if !focused {
+                    // Releases are not reported to unfocused windows, so
+                    // anything held would otherwise get stuck
+                    self._keys_held.clear();
+                    self._buttons_held.clear();
+                    self._modifiers = Modifiers::default();
+                }
+            }
+            Event::GamepadConnected(id) => {
+                self._gamepads.insert(*id, GamepadState::default());
+            }
+            Event::GamepadDisconnected(id) => {
+                self._gamepads.remove(id);
+            }
+            Event::GamepadButton { id, button, action } => {
+                let state = self._gamepads.entry(*id).or_default();
+                match action {
+                    Action::Press => {
+                        state.set_button(*button, true);
+                        self._gamepad_buttons_pressed.insert((*id, *button));
+                    }
+                    Action::Release => {
+                        state.set_button(*button, false);
+                        self._gamepad_buttons_released.insert((*id, *button));
+                    }
+                    Action::Repeat => {}
+                }
+            }
+            Event::GamepadAxis { id, axis, value } => {
+                self._gamepads
+                    .entry(*id)
+                    .or_default()
+                    .set_axis(*axis, *value);
+            }
+            _ => {}
+        }
+    }
+
+    /// Get whether a key is currently held down.
+    pub fn is_key_held(&self, key: Key) -> bool {
+        return self._keys_held.contains(&key);
+    }
+
+    /// Get whether a key was pressed this frame.
+    pub fn was_key_pressed(&self, key: Key) -> bool {
+        return self._keys_pressed.contains(&key);
+    }
+
+    /// Get whether a key was released this frame.
+    pub fn was_key_released(&self, key: Key) -> bool {
+        return self._keys_released.contains(&key);
+    }
+
+    /// Get whether a mouse button is currently held down.
+    pub fn is_mouse_button_held(&self, button: MouseButton) -> bool {
+        return self._buttons_held.contains(&button);
+    }
+
+    /// Get whether a mouse button was pressed this frame.
+    pub fn was_mouse_button_pressed(&self, button: MouseButton) -> bool {
+        return self._buttons_pressed.contains(&button);
+    }
+
+    /// Get whether a mouse button was released this frame.
+    pub fn was_mouse_button_released(&self, button: MouseButton) -> bool {
+        return self._buttons_released.contains(&button);
+    }
+
+    /// Get the modifiers reported by the last key or mouse button event.
+    pub fn get_modifiers(&self) -> Modifiers {
+        return self._modifiers;
+    }
+
+    /// Get the last known cursor position, if the cursor has moved at all.
+    pub fn get_cursor_position(&self) -> Option<(f64, f64)> {
+        return self._cursor_position;
+    }
+
+    /// Get whether the cursor is inside the window.
+    pub fn is_cursor_inside(&self) -> bool {
+        return self._cursor_inside;
+    }
+
+    /// Get how much the cursor moved since the last frame.
+    pub fn get_mouse_delta(&self) -> (f64, f64) {
+        return self._mouse_delta;
+    }
+
+    /// Get how much was scrolled since the last frame.
+    pub fn get_scroll_delta(&self) -> (f64, f64) {
+        return self._scroll_delta;
+    }
+
+    /// Get whether the window has focus.
+    pub fn is_focused(&self) -> bool {
+        return self._focused;
+    }
+
+    /// Get the ids of the connected gamepads, in increasing order.
+    pub fn get_gamepads(&self) -> Vec<u32> {
+        let mut ids: Vec<u32> = self._gamepads.keys().copied().collect();
+        ids.sort();
+        return ids;
+    }
+
+    /// Get the state of a gamepad, if it is connected.
+    pub fn get_gamepad(&self, id: u32) -> Option<&GamepadState> {
+        return self._gamepads.get(&id);
+    }
+
+    /// Get whether a gamepad button is currently held down.
+    pub fn is_gamepad_button_held(&self, id: u32, button: GamepadButton) -> bool {
+        return self
+            ._gamepads
+            .get(&id)
+            .is_some_and(|s| s.is_button_held(button));
+    }
+
+    /// Get whether a gamepad button was pressed this frame.
+    pub fn was_gamepad_button_pressed(&self, id: u32, button: GamepadButton) -> bool {
+        return self._gamepad_buttons_pressed.contains(&(id, button));
+    }
+
+    /// Get whether a gamepad button was released this frame.
+    pub fn was_gamepad_button_released(&self, id: u32, button: GamepadButton) -> bool {
+        return self._gamepad_buttons_released.contains(&(id, button));
+    }
+
+    /// Get the value of a gamepad axis, or 0 if the gamepad is not
+    /// connected.
+    pub fn get_gamepad_axis(&self, id: u32, axis: GamepadAxis) -> f32 {
+        return self._gamepads.get(&id).map_or(0.0, |s| s.get_axis(axis));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(key: Key, action: Action) -> Event {
+        return Event::Key {
+            key,
+            scancode: 0,
+            action,
+            modifiers: Modifiers::default(),
+        };
+    }
+
+    fn button(button: MouseButton, action: Action) -> Event {
+        return Event::MouseButton {
+            button,
+            action,
+            modifiers: Modifiers::default(),
+        };
+    }
+
+    #[test]
+    fn key_press_is_reported_for_one_frame() {
+        let mut state = InputState::new();
+        state.begin_frame();
+        state.update(&key(Key::A, Action::Press));
+        assert!(state.was_key_pressed(Key::A));
+        assert!(state.is_key_held(Key::A));
+        assert!(!state.was_key_released(Key::A));
+
+        state.begin_frame();
+        assert!(!state.was_key_pressed(Key::A));
+        assert!(state.is_key_held(Key::A));
+    }
+
+    #[test]
+    fn key_release_is_reported_for_one_frame() {
+        let mut state = InputState::new();
+        state.update(&key(Key::A, Action::Press));
+        state.begin_frame();
+        state.update(&key(Key::A, Action::Release));
+        assert!(state.was_key_released(Key::A));
+        assert!(!state.is_key_held(Key::A));
+
+        state.begin_frame();
+        assert!(!state.was_key_released(Key::A));
+        assert!(!state.is_key_held(Key::A));
+    }
+
+    #[test]
+    fn press_and_release_in_one_frame_reports_both_edges() {
+        let mut state = InputState::new();
+        state.begin_frame();
+        state.update(&key(Key::Space, Action::Press));
+        state.update(&key(Key::Space, Action::Release));
+        assert!(state.was_key_pressed(Key::Space));
+        assert!(state.was_key_released(Key::Space));
+        assert!(!state.is_key_held(Key::Space));
+    }
+
+    #[test]
+    fn repeat_is_not_a_press() {
+        let mut state = InputState::new();
+        state.update(&key(Key::A, Action::Press));
+        state.begin_frame();
+        state.update(&key(Key::A, Action::Repeat));
+        assert!(!state.was_key_pressed(Key::A));
+        assert!(state.is_key_held(Key::A));
+    }
+
+    #[test]
+    fn mouse_button_edges() {
+        let mut state = InputState::new();
+        state.begin_frame();
+        state.update(&button(MouseButton::Left, Action::Press));
+        assert!(state.was_mouse_button_pressed(MouseButton::Left));
+        assert!(state.is_mouse_button_held(MouseButton::Left));
+
+        state.begin_frame();
+        state.update(&button(MouseButton::Left, Action::Release));
+        assert!(!state.was_mouse_button_pressed(MouseButton::Left));
+        assert!(state.was_mouse_button_released(MouseButton::Left));
+        assert!(!state.is_mouse_button_held(MouseButton::Left));
+    }
+
+    #[test]
+    fn mouse_and_scroll_deltas_reset_every_frame() {
+        let mut state = InputState::new();
+        state.update(&Event::CursorMoved { x: 10.0, y: 10.0 });
+        assert_eq!(state.get_mouse_delta(), (0.0, 0.0));
+
+        state.begin_frame();
+        state.update(&Event::CursorMoved { x: 13.0, y: 8.0 });
+        state.update(&Event::CursorMoved { x: 15.0, y: 9.0 });
+        state.update(&Event::Scroll { x: 0.0, y: 1.0 });
+        state.update(&Event::Scroll { x: 0.0, y: 2.0 });
+        assert_eq!(state.get_mouse_delta(), (5.0, -1.0));
+        assert_eq!(state.get_scroll_delta(), (0.0, 3.0));
+        assert_eq!(state.get_cursor_position(), Some((15.0, 9.0)));
+
+        state.begin_frame();
+        assert_eq!(state.get_mouse_delta(), (0.0, 0.0));
+        assert_eq!(state.get_scroll_delta(), (0.0, 0.0));
+    }
+
+    #[test]
+    fn losing_focus_releases_everything() {
+        let mut state = InputState::new();
+        state.update(&Event::Focused(true));
+        state.update(&Event::Key {
+            key: Key::LeftShift,
+            scancode: 0,
+            action: Action::Press,
+            modifiers: Modifiers {
+                shift: true,
+                ..Default::default()
+            },
+        });
+        state.update(&button(MouseButton::Right, Action::Press));
+        state.update(&Event::Focused(false));
+        assert!(!state.is_focused());
+        assert!(!state.is_key_held(Key::LeftShift));
+        assert!(!state.is_mouse_button_held(MouseButton::Right));
+        assert_eq!(state.get_modifiers(), Modifiers::default());
+    }
+
+    #[test]
+    fn gamepad_button_edges() {
+        let mut state = InputState::new();
+        state.update(&Event::GamepadConnected(1));
+        state.begin_frame();
+        state.update(&Event::GamepadButton {
+            id: 1,
+            button: GamepadButton::A,
+            action: Action::Press,
+        });
+        assert!(state.was_gamepad_button_pressed(1, GamepadButton::A));
+        assert!(state.is_gamepad_button_held(1, GamepadButton::A));
+
+        state.begin_frame();
+        state.update(&Event::GamepadButton {
+            id: 1,
+            button: GamepadButton::A,
+            action: Action::Release,
+        });
+        assert!(!state.was_gamepad_button_pressed(1, GamepadButton::A));
+        assert!(state.was_gamepad_button_released(1, GamepadButton::A));
+        assert!(!state.is_gamepad_button_held(1, GamepadButton::A));
+
+        state.update(&Event::GamepadDisconnected(1));
+        assert!(state.get_gamepads().is_empty());
+    }
+}
@@ -1,3 +1,13 @@
+#[cfg(feature = "serde")]
+mod config;
+mod error;
+mod headless;
+mod input;
+mod monitor;
 mod window;
 
+pub use error::*;
+pub use headless::*;
+pub use input::*;
+pub use monitor::*;
 pub use window::*;
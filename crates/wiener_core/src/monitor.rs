@@ -0,0 +1,109 @@
+use crate::WindowError;
+
+use glfw;
+
+/// Video mode supported by a monitor.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VideoMode {
+    pub width: u32,
+    pub height: u32,
+    pub red_bits: u32,
+    pub green_bits: u32,
+    pub blue_bits: u32,
+    pub refresh_rate: u32,
+}
+
+impl From<glfw::VidMode> for VideoMode {
+    fn from(mode: glfw::VidMode) -> Self {
+        return VideoMode {
+            width: mode.width,
+            height: mode.height,
+            red_bits: mode.red_bits,
+            green_bits: mode.green_bits,
+            blue_bits: mode.blue_bits,
+            refresh_rate: mode.refresh_rate,
+        };
+    }
+}
+
+/// Information about a connected monitor.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MonitorInfo {
+    /// Index of the monitor, as used by `WindowMode`. The primary monitor
+    /// always has index 0.
+    pub index: u32,
+
+    /// Human readable name of the monitor.
+    pub name: String,
+
+    /// Position of the monitor in the virtual screen, in screen coordinates.
+    pub position: (i32, i32),
+
+    /// Physical size of the monitor in millimetres.
+    pub physical_size: (i32, i32),
+
+    /// Content scale of the monitor, i.e. the ratio between the current DPI
+    /// and the platform's default DPI.
+    pub content_scale: (f32, f32),
+
+    /// Area of the monitor not occupied by task bars or menu bars, given as
+    /// (x, y, width, height).
+    pub work_area: (i32, i32, i32, i32),
+
+    /// Video mode currently used by the monitor.
+    pub current_mode: Option<VideoMode>,
+
+    /// Every video mode supported by the monitor.
+    pub video_modes: Vec<VideoMode>,
+}
+
+impl MonitorInfo {
+    /// Collect the information of a GLFW monitor.
+    pub fn from_glfw(index: u32, monitor: &glfw::Monitor) -> Self {
+        return MonitorInfo {
+            index,
+            name: monitor.get_name().unwrap_or_default(),
+            position: monitor.get_pos(),
+            physical_size: monitor.get_physical_size(),
+            content_scale: monitor.get_content_scale(),
+            work_area: monitor.get_workarea(),
+            current_mode: monitor.get_video_mode().map(VideoMode::from),
+            video_modes: monitor
+                .get_video_modes()
+                .into_iter()
+                .map(VideoMode::from)
+                .collect(),
+        };
+    }
+
+    /// Get whether this is the primary monitor or not.
+    pub fn is_primary(&self) -> bool {
+        return self.index == 0;
+    }
+}
+
+/// Get the information of every connected monitor, with the primary monitor
+/// first.
+pub fn get_monitors(glfw_inst: &mut glfw::Glfw) -> Vec<MonitorInfo> {
+    return glfw_inst.with_connected_monitors(|_, monitors| {
+        return monitors
+            .iter()
+            .enumerate()
+            .map(|(i, m)| MonitorInfo::from_glfw(i as u32, m))
+            .collect();
+    });
+}
+
+/// Supply the monitor with the given index to the closure, failing if it is
+/// not connected.
+pub fn with_monitor<T, F>(glfw_inst: &mut glfw::Glfw, index: u32, f: F) -> Result<T, WindowError>
+where
+    F: FnOnce(&mut glfw::Glfw, &glfw::Monitor) -> T,
+{
+    return glfw_inst.with_connected_monitors(|temp_glfw, monitors| {
+        return match monitors.get(index as usize) {
+            Some(m) => Ok(f(temp_glfw, m)),
+            None => Err(WindowError::MonitorNotFound(index)),
+        };
+    });
+}
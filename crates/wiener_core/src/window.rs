@@ -1,18 +1,75 @@
+use crate::{with_monitor, WindowError};
+
 use glfw;
 use glfw::Context;
+use std::cell::RefCell;
 use std::sync::mpsc::Receiver;
 
 use log;
 
-/// Mode for the window.
-#[derive(Clone, Copy, Debug)]
+/// Mode for the window. Monitors are identified by their index, where the
+/// primary monitor is always 0.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum WindowMode {
     Windowed,
+    /// Exclusive fullscreen on the given monitor, using the descriptor's
+    /// dimensions as the video mode.
     FullScreen(u32),
+    /// Windowed fullscreen on the given monitor, keeping its current video
+    /// mode so no mode switch happens.
+    Borderless(u32),
+}
+
+/// Number of screen updates to wait for before swapping the buffers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SwapInterval {
+    /// Swap immediately, without vertical synchronization.
+    None,
+    /// Wait for the given number of screen updates.
+    Sync(u32),
+    /// Wait for the next screen update unless the frame is late, in which
+    /// case swap immediately. Requires driver support for adaptive vsync.
+    Adaptive,
+}
+
+impl From<SwapInterval> for glfw::SwapInterval {
+    fn from(interval: SwapInterval) -> Self {
+        return match interval {
+            SwapInterval::None => glfw::SwapInterval::None,
+            SwapInterval::Sync(n) => glfw::SwapInterval::Sync(n),
+            SwapInterval::Adaptive => glfw::SwapInterval::Adaptive,
+        };
+    }
+}
+
+/// Behaviour of the cursor inside the window.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CursorMode {
+    Normal,
+    /// The cursor is invisible while over the window, but moves freely.
+    Hidden,
+    /// The cursor is hidden and locked to the window, for unlimited
+    /// movement (e.g. camera controls).
+    Disabled,
+}
+
+impl From<CursorMode> for glfw::CursorMode {
+    fn from(mode: CursorMode) -> Self {
+        return match mode {
+            CursorMode::Normal => glfw::CursorMode::Normal,
+            CursorMode::Hidden => glfw::CursorMode::Hidden,
+            CursorMode::Disabled => glfw::CursorMode::Disabled,
+        };
+    }
 }
 
 /// Descriptor of a window.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct WindowDescriptor {
     pub width: i32,
     pub height: i32,
@@ -23,8 +80,29 @@ pub struct WindowDescriptor {
     pub cursor_pos_polling: bool,
     pub mouse_button_polling: bool,
     pub framebuffer_size_polling: bool,
-    pub cursor_mode: glfw::CursorMode,
+    pub scroll_polling: bool,
+    pub focus_polling: bool,
+    pub drag_and_drop_polling: bool,
+    pub gamepad_polling: bool,
+    /// Radius of the deadzone applied to gamepad sticks, from 0 to 0.99.
+    /// Values out of range are clamped by the `GamepadPoller`.
+    pub gamepad_deadzone: f32,
+    pub cursor_mode: CursorMode,
     pub make_current: bool,
+    /// Whether to create a surfaceless context through EGL instead of a
+    /// window, which needs no display server (see `init_headless`).
+    pub headless: bool,
+    pub swap_interval: SwapInterval,
+    pub resizable: bool,
+    pub decorated: bool,
+    pub floating: bool,
+    pub transparent: bool,
+    /// Number of MSAA samples of the default framebuffer, where 0 disables
+    /// multisampling.
+    pub samples: u32,
+    pub srgb: bool,
+    pub debug_context: bool,
+    pub forward_compat: bool,
 }
 
 impl WindowDescriptor {
@@ -78,6 +156,12 @@ impl WindowDescriptor {
         return self;
     }
 
+    /// Make the window borderless fullscreen.
+    pub fn set_borderless(mut self, monitor: u32) -> Self {
+        self.mode = WindowMode::Borderless(monitor);
+        return self;
+    }
+
     /// Set the key polling event.
     pub fn set_key_polling(mut self, key_polling: bool) -> Self {
         self.key_polling = key_polling;
@@ -108,8 +192,39 @@ impl WindowDescriptor {
         return self;
     }
 
+    /// Set the scroll polling event.
+    pub fn set_scroll_polling(mut self, scroll_polling: bool) -> Self {
+        self.scroll_polling = scroll_polling;
+        return self;
+    }
+
+    /// Set the focus polling event.
+    pub fn set_focus_polling(mut self, focus_polling: bool) -> Self {
+        self.focus_polling = focus_polling;
+        return self;
+    }
+
+    /// Set the drag and drop polling event, which reports the paths of the
+    /// files dropped onto the window.
+    pub fn set_drag_and_drop_polling(mut self, drag_and_drop_polling: bool) -> Self {
+        self.drag_and_drop_polling = drag_and_drop_polling;
+        return self;
+    }
+
+    /// Set the gamepad polling.
+    pub fn set_gamepad_polling(mut self, gamepad_polling: bool) -> Self {
+        self.gamepad_polling = gamepad_polling;
+        return self;
+    }
+
+    /// Set the radius of the deadzone applied to gamepad sticks.
+    pub fn set_gamepad_deadzone(mut self, gamepad_deadzone: f32) -> Self {
+        self.gamepad_deadzone = gamepad_deadzone;
+        return self;
+    }
+
     /// Set the cursor mode.
-    pub fn set_cursor_mode(mut self, cursor_mode: glfw::CursorMode) -> Self {
+    pub fn set_cursor_mode(mut self, cursor_mode: CursorMode) -> Self {
         self.cursor_mode = cursor_mode;
         return self;
     }
@@ -119,6 +234,82 @@ impl WindowDescriptor {
         self.make_current = make_current;
         return self;
     }
+
+    /// Set whether the window is headless or not. A headless window has no
+    /// window or default framebuffer, so it can be used for offscreen
+    /// rendering (e.g. thumbnails or regression images) on machines without
+    /// a display server.
+    pub fn set_headless(mut self, headless: bool) -> Self {
+        self.headless = headless;
+        return self;
+    }
+
+    /// Set the swap interval.
+    pub fn set_swap_interval(mut self, swap_interval: SwapInterval) -> Self {
+        self.swap_interval = swap_interval;
+        return self;
+    }
+
+    /// Enable or disable vsync, i.e. set the swap interval to 1 or 0.
+    pub fn set_vsync(mut self, vsync: bool) -> Self {
+        self.swap_interval = if vsync {
+            SwapInterval::Sync(1)
+        } else {
+            SwapInterval::None
+        };
+        return self;
+    }
+
+    /// Set whether the window can be resized by the user or not.
+    pub fn set_resizable(mut self, resizable: bool) -> Self {
+        self.resizable = resizable;
+        return self;
+    }
+
+    /// Set whether the window has decorations (border, title bar, etc.) or
+    /// not.
+    pub fn set_decorated(mut self, decorated: bool) -> Self {
+        self.decorated = decorated;
+        return self;
+    }
+
+    /// Set whether the window floats on top of other windows or not.
+    pub fn set_floating(mut self, floating: bool) -> Self {
+        self.floating = floating;
+        return self;
+    }
+
+    /// Set whether the framebuffer is transparent or not, if supported by
+    /// the system.
+    pub fn set_transparent(mut self, transparent: bool) -> Self {
+        self.transparent = transparent;
+        return self;
+    }
+
+    /// Set the number of MSAA samples of the default framebuffer.
+    pub fn set_samples(mut self, samples: u32) -> Self {
+        self.samples = samples;
+        return self;
+    }
+
+    /// Set whether the default framebuffer is sRGB capable or not.
+    pub fn set_srgb(mut self, srgb: bool) -> Self {
+        self.srgb = srgb;
+        return self;
+    }
+
+    /// Set whether to request a debug context or not.
+    pub fn set_debug_context(mut self, debug_context: bool) -> Self {
+        self.debug_context = debug_context;
+        return self;
+    }
+
+    /// Set whether to request a forward compatible context or not, which is
+    /// required for core profiles on macOS.
+    pub fn set_forward_compat(mut self, forward_compat: bool) -> Self {
+        self.forward_compat = forward_compat;
+        return self;
+    }
 }
 
 impl Default for WindowDescriptor {
@@ -133,59 +324,291 @@ impl Default for WindowDescriptor {
             cursor_pos_polling: true,
             mouse_button_polling: true,
             framebuffer_size_polling: true,
-            cursor_mode: glfw::CursorMode::Normal,
+            scroll_polling: true,
+            focus_polling: true,
+            drag_and_drop_polling: true,
+            gamepad_polling: true,
+            gamepad_deadzone: 0.15,
+            cursor_mode: CursorMode::Normal,
             make_current: true,
+            headless: false,
+            swap_interval: SwapInterval::Sync(1),
+            resizable: true,
+            decorated: true,
+            floating: false,
+            transparent: false,
+            samples: 0,
+            srgb: false,
+            debug_context: false,
+            forward_compat: false,
         };
     }
 }
 
-/// Initializes a GLFW window, setting it as the current one.
-pub fn init_glfw(
+/// Window and event receiver created by GLFW.
+type GlfwWindowPair = (glfw::Window, Receiver<(f64, glfw::WindowEvent)>);
+
+/// Window, event receiver and GLFW instance created by `init_glfw`.
+pub type GlfwHandles = (glfw::Window, Receiver<(f64, glfw::WindowEvent)>, glfw::Glfw);
+
+thread_local! {
+    /// Last error reported by GLFW in this thread.
+    static LAST_GLFW_ERROR: RefCell<Option<(glfw::Error, String)>> = const { RefCell::new(None) };
+}
+
+/// Error callback for GLFW, which logs the error and stores it so it can be
+/// reported as a `WindowError`.
+fn glfw_error_callback(error: glfw::Error, description: String, _: &()) {
+    log::warn!("init_glfw :: GLFW error {:?}: {}", error, description);
+    LAST_GLFW_ERROR.with(|e| *e.borrow_mut() = Some((error, description)));
+}
+
+/// Create a window with the hints that are currently set, sharing its
+/// context with another window if given.
+fn create_glfw_window(
+    glfw_inst: &mut glfw::Glfw,
+    shared: Option<&glfw::Window>,
+    width: u32,
+    height: u32,
+    title: &str,
+    mode: glfw::WindowMode,
+) -> Option<GlfwWindowPair> {
+    return match shared {
+        Some(w) => w.create_shared(width, height, title, mode),
+        None => glfw_inst.create_window(width, height, title, mode),
+    };
+}
+
+/// Create a window in the given mode with the hints that are currently set.
+fn create_window(
+    glfw_inst: &mut glfw::Glfw,
+    shared: Option<&glfw::Window>,
     descriptor: &WindowDescriptor,
-    version: (u32, u32),
-    profile: glfw::OpenGlProfileHint,
-) -> (glfw::Window, Receiver<(f64, glfw::WindowEvent)>, glfw::Glfw) {
-    let mut glfw_inst = glfw::init(glfw::FAIL_ON_ERRORS).unwrap();
-    log::info!("init_glfw :: Initializing GLFW");
+    mode: WindowMode,
+) -> Result<Option<GlfwWindowPair>, WindowError> {
+    let width = descriptor.width as u32;
+    let height = descriptor.height as u32;
+    return match mode {
+        WindowMode::Windowed => Ok(create_glfw_window(
+            glfw_inst,
+            shared,
+            width,
+            height,
+            &descriptor.title,
+            glfw::WindowMode::Windowed,
+        )),
+        WindowMode::FullScreen(monitor) => with_monitor(glfw_inst, monitor, |temp_glfw, m| {
+            return create_glfw_window(
+                temp_glfw,
+                shared,
+                width,
+                height,
+                &descriptor.title,
+                glfw::WindowMode::FullScreen(m),
+            );
+        }),
+        WindowMode::Borderless(monitor) => with_monitor(glfw_inst, monitor, |temp_glfw, m| {
+            let (width, height) = match m.get_video_mode() {
+                Some(vm) => {
+                    // Matching the current mode makes GLFW skip the mode switch
+                    temp_glfw.window_hint(glfw::WindowHint::RedBits(Some(vm.red_bits)));
+                    temp_glfw.window_hint(glfw::WindowHint::GreenBits(Some(vm.green_bits)));
+                    temp_glfw.window_hint(glfw::WindowHint::BlueBits(Some(vm.blue_bits)));
+                    temp_glfw.window_hint(glfw::WindowHint::RefreshRate(Some(vm.refresh_rate)));
+                    (vm.width, vm.height)
+                }
+                None => (width, height),
+            };
+            return create_glfw_window(
+                temp_glfw,
+                shared,
+                width,
+                height,
+                &descriptor.title,
+                glfw::WindowMode::FullScreen(m),
+            );
+        }),
+    };
+}
 
+/// Set the window hints described by the descriptor, resetting any hint set
+/// for a previous window.
+fn set_window_hints(
+    glfw_inst: &mut glfw::Glfw,
+    descriptor: &WindowDescriptor,
+    profile: glfw::OpenGlProfileHint,
+) {
+    glfw_inst.default_window_hints();
     glfw_inst.window_hint(glfw::WindowHint::CenterCursor(true));
-    glfw_inst.window_hint(glfw::WindowHint::ContextVersion(version.0, version.1));
-    // glfw_inst.window_hint(glfw::WindowHint::OpenGlForwardCompat(true));
     glfw_inst.window_hint(glfw::WindowHint::OpenGlProfile(profile));
+    glfw_inst.window_hint(glfw::WindowHint::OpenGlForwardCompat(
+        descriptor.forward_compat,
+    ));
+    glfw_inst.window_hint(glfw::WindowHint::OpenGlDebugContext(
+        descriptor.debug_context,
+    ));
+    glfw_inst.window_hint(glfw::WindowHint::Resizable(descriptor.resizable));
+    glfw_inst.window_hint(glfw::WindowHint::Decorated(descriptor.decorated));
+    glfw_inst.window_hint(glfw::WindowHint::Floating(descriptor.floating));
+    glfw_inst.window_hint(glfw::WindowHint::TransparentFramebuffer(
+        descriptor.transparent,
+    ));
+    glfw_inst.window_hint(glfw::WindowHint::Samples(Some(descriptor.samples)));
+    glfw_inst.window_hint(glfw::WindowHint::SRgbCapable(descriptor.srgb));
+}
 
-    let (mut window, events) = match descriptor.mode {
-        WindowMode::Windowed => glfw_inst
-            .create_window(
-                descriptor.width as u32,
-                descriptor.height as u32,
-                &descriptor.title,
-                glfw::WindowMode::Windowed,
-            )
-            .expect("Error creating GLFW window"),
-        WindowMode::FullScreen(_) => glfw_inst
-            .with_primary_monitor(|temp_glfw, m| {
-                temp_glfw.create_window(
-                    descriptor.width as u32,
-                    descriptor.height as u32,
-                    &descriptor.title,
-                    m.map_or(glfw::WindowMode::Windowed, |m| {
-                        glfw::WindowMode::FullScreen(m)
-                    }),
-                )
-            })
-            .expect("Error creating GLFW window"),
+/// Get the error for a window that could not be created, from the last error
+/// reported by GLFW.
+fn creation_error(versions: &[(u32, u32)], profile: glfw::OpenGlProfileHint) -> WindowError {
+    return match LAST_GLFW_ERROR.with(|e| e.borrow_mut().take()) {
+        Some((glfw::Error::VersionUnavailable | glfw::Error::ApiUnavailable, _)) | None => {
+            WindowError::UnsupportedContext {
+                versions: versions.to_vec(),
+                profile,
+            }
+        }
+        Some((_, description)) => WindowError::WindowCreation(description),
     };
+}
 
+/// Configure a created window as described by the descriptor.
+fn configure_window(
+    glfw_inst: &mut glfw::Glfw,
+    window: &mut glfw::Window,
+    descriptor: &WindowDescriptor,
+) {
     log::info!("init_glfw :: Configuring window");
     window.set_key_polling(descriptor.key_polling);
-    window.set_cursor_mode(descriptor.cursor_mode);
+    window.set_cursor_mode(descriptor.cursor_mode.into());
     window.set_cursor_enter_polling(descriptor.cursor_enter_polling);
     window.set_mouse_button_polling(descriptor.mouse_button_polling);
     window.set_cursor_pos_polling(descriptor.cursor_pos_polling);
     window.set_framebuffer_size_polling(descriptor.framebuffer_size_polling);
+    window.set_scroll_polling(descriptor.scroll_polling);
+    window.set_focus_polling(descriptor.focus_polling);
+    window.set_drag_and_drop_polling(descriptor.drag_and_drop_polling);
     if descriptor.make_current {
         window.make_current();
+        glfw_inst.set_swap_interval(descriptor.swap_interval.into());
+    } else {
+        log::info!("init_glfw :: Not current, so the swap interval is not set");
+    }
+}
+
+/// Switch the mode of an existing window. The rectangle, given as
+/// (x, y, width, height), is used for the position and size when going
+/// windowed, and its size is used as the video mode for exclusive
+/// fullscreen.
+pub fn set_window_mode(
+    glfw_inst: &mut glfw::Glfw,
+    window: &mut glfw::Window,
+    mode: WindowMode,
+    windowed_rect: (i32, i32, i32, i32),
+) -> Result<(), WindowError> {
+    log::info!("set_window_mode :: Switching window to mode {:?}", mode);
+    let (x, y, width, height) = windowed_rect;
+    match mode {
+        WindowMode::Windowed => window.set_monitor(
+            glfw::WindowMode::Windowed,
+            x,
+            y,
+            width as u32,
+            height as u32,
+            None,
+        ),
+        WindowMode::FullScreen(monitor) => with_monitor(glfw_inst, monitor, |_, m| {
+            window.set_monitor(
+                glfw::WindowMode::FullScreen(m),
+                0,
+                0,
+                width as u32,
+                height as u32,
+                None,
+            );
+        })?,
+        WindowMode::Borderless(monitor) => with_monitor(glfw_inst, monitor, |_, m| {
+            let vm = m.get_video_mode();
+            window.set_monitor(
+                glfw::WindowMode::FullScreen(m),
+                0,
+                0,
+                vm.map_or(width as u32, |vm| vm.width),
+                vm.map_or(height as u32, |vm| vm.height),
+                vm.map(|vm| vm.refresh_rate),
+            );
+        })?,
+    };
+    return Ok(());
+}
+
+/// Initializes a GLFW window, setting it as the current one.
+///
+/// The context versions are tried in order, and the first one that can be
+/// created with the given profile is used. The `headless` field of the
+/// descriptor is not used here; for rendering without a display server, use
+/// `init_headless` instead.
+pub fn init_glfw(
+    descriptor: &WindowDescriptor,
+    versions: &[(u32, u32)],
+    profile: glfw::OpenGlProfileHint,
+) -> Result<GlfwHandles, WindowError> {
+    log::info!("init_glfw :: Initializing GLFW");
+    LAST_GLFW_ERROR.with(|e| *e.borrow_mut() = None);
+    let mut glfw_inst = glfw::init(Some(glfw::Callback {
+        f: glfw_error_callback as fn(glfw::Error, String, &()),
+        data: (),
+    }))?;
+    set_window_hints(&mut glfw_inst, descriptor, profile);
+
+    let mode = descriptor.mode;
+    let mut created = None;
+    for version in versions {
+        log::info!(
+            "init_glfw :: Trying context version {:?}.{:?}",
+            version.0,
+            version.1
+        );
+        glfw_inst.window_hint(glfw::WindowHint::ContextVersion(version.0, version.1));
+        created = create_window(&mut glfw_inst, None, descriptor, mode)?;
+        if created.is_some() {
+            break;
+        }
     }
+    let (mut window, events) = match created {
+        Some(pair) => pair,
+        None => return Err(creation_error(versions, profile)),
+    };
+
+    configure_window(&mut glfw_inst, &mut window, descriptor);
+    return Ok((window, events, glfw_inst));
+}
+
+/// Initializes a GLFW window whose context shares objects (buffers, textures,
+/// shader programs, etc.) with the context of another window.
+///
+/// The context is created with the given version and profile, which should
+/// match the ones of the other window. Container objects such as vertex
+/// arrays and framebuffers are never shared between contexts, so they must be
+/// created separately for each window.
+pub fn init_shared_glfw(
+    shared: &glfw::Window,
+    descriptor: &WindowDescriptor,
+    version: (u32, u32),
+    profile: glfw::OpenGlProfileHint,
+) -> Result<GlfwHandles, WindowError> {
+    log::info!("init_shared_glfw :: Creating shared window");
+    LAST_GLFW_ERROR.with(|e| *e.borrow_mut() = None);
+    let mut glfw_inst = shared.glfw.clone();
+    set_window_hints(&mut glfw_inst, descriptor, profile);
+    glfw_inst.window_hint(glfw::WindowHint::ContextVersion(version.0, version.1));
+
+    let mode = descriptor.mode;
+    let (mut window, events) = match create_window(&mut glfw_inst, Some(shared), descriptor, mode)?
+    {
+        Some(pair) => pair,
+        None => return Err(creation_error(&[version], profile)),
+    };
 
-    return (window, events, glfw_inst);
+    configure_window(&mut glfw_inst, &mut window, descriptor);
+    return Ok((window, events, glfw_inst));
 }
@@ -0,0 +1,91 @@
+use crate::{GLManager, GLWindow};
+
+use log;
+use wiener_core::Event;
+
+/// Maximum time simulated in a single frame, in seconds. Longer frames (e.g.
+/// after a breakpoint or while dragging the window) are clamped so the
+/// simulation does not spiral trying to catch up, and the clock going back
+/// (e.g. when a replay ends) simulates nothing.
+const MAX_FRAME_TIME: f64 = 0.25;
+
+/// Application driven by `run`.
+///
+/// The simulation advances in fixed steps of `get_timestep` seconds, while
+/// rendering happens once per frame with the interpolation factor between
+/// the last two simulation steps.
+pub trait App {
+    /// Get the duration of a simulation step, in seconds, which must be
+    /// positive and finite.
+    fn get_timestep(&self) -> f64 {
+        return 1.0 / 60.0;
+    }
+
+    /// Initialize the application, right before the first frame.
+    fn init(&mut self, _window: &mut GLWindow) {}
+
+    /// Advance the simulation by `dt` seconds.
+    fn update(&mut self, window: &mut GLWindow, dt: f32);
+
+    /// Render a frame, where `alpha` is how far the current time is between
+    /// the previous and the current simulation step, in [0, 1).
+    fn render(&mut self, window: &mut GLWindow, alpha: f32);
+
+    /// Handle an event polled from the window.
+    fn on_event(&mut self, _window: &mut GLWindow, _event: &Event) {}
+
+    /// Handle a resize of the framebuffer. By default the viewport is set to
+    /// cover the whole framebuffer.
+    fn on_resize(&mut self, _window: &mut GLWindow, width: i32, height: i32) {
+        GLManager::viewport(0, 0, width, height);
+    }
+}
+
+/// Run the application until the window should close.
+///
+/// Panics if the timestep of the application is not positive and finite.
+///
+/// The application is dropped before the window, so every resource it owns
+/// is deleted while the context is still current.
+pub fn run<A: App>(mut window: GLWindow, mut app: A) {
+    log::info!("run :: Initializing application");
+    app.init(&mut window);
+    let (width, height) = window.get_framebuffer_size();
+    app.on_resize(&mut window, width, height);
+
+    let timestep = app.get_timestep();
+    assert!(
+        timestep > 0.0 && timestep.is_finite(),
+        "The timestep must be positive and finite, got {}",
+        timestep
+    );
+    let mut accumulator = 0.0;
+    let mut last_time = window.get_time_f64();
+
+    log::info!("run :: Starting the main loop");
+    while !window.should_close() {
+        window.poll_events();
+        let events = window.get_events().to_vec();
+        for event in &events {
+            app.on_event(&mut window, event);
+            if let Event::Resized { width, height } = event {
+                app.on_resize(&mut window, *width, *height);
+            }
+        }
+
+        let current_time = window.get_time_f64();
+        accumulator += (current_time - last_time).clamp(0.0, MAX_FRAME_TIME);
+        last_time = current_time;
+        while accumulator >= timestep {
+            app.update(&mut window, timestep as f32);
+            accumulator -= timestep;
+        }
+
+        app.render(&mut window, (accumulator / timestep) as f32);
+        window.swap_buffers();
+    }
+
+    log::info!("run :: Shutting down");
+    drop(app);
+    drop(window);
+}
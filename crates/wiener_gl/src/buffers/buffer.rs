@@ -1,5 +1,308 @@
-/// Object that represents a buffer to the GPU.
-pub trait Buffer {
-    /// Buffer data to this space in GPU memory.
-    fn buffer_data<T>(&self, data: &[T]);
+use crate::{DataType, Fence, HasContext, StateCache};
+use std::marker::PhantomData;
+use std::mem::size_of;
+use std::ptr::null;
+
+use gl;
+use gl::types::*;
+use log;
+
+/// Plain data that can be copied to GPU memory byte by byte, as defined by
+/// `bytemuck`. Vertex structs can derive it along with `Zeroable` (which
+/// needs `bytemuck` as a dependency), as long as they are `#[repr(C)]` and
+/// have no padding.
+pub use bytemuck::{Pod, Zeroable};
+
+/// Type of the indices stored in an element buffer.
+pub trait Index: Pod {
+    /// Data type passed to the draw calls.
+    const DATA_TYPE: DataType;
+}
+
+impl Index for u8 {
+    const DATA_TYPE: DataType = DataType::UnsignedByte;
+}
+
+impl Index for u16 {
+    const DATA_TYPE: DataType = DataType::UnsignedShort;
+}
+
+impl Index for u32 {
+    const DATA_TYPE: DataType = DataType::UnsignedInt;
+}
+
+/// Object that represents a buffer to the GPU, holding elements of a single
+/// type. The buffer remembers how many elements it holds (its length) and
+/// how many fit in its storage (its capacity), and only reallocates when the
+/// data no longer fits.
+pub trait Buffer: HasContext {
+    /// Type of the elements in the buffer.
+    type Element: Pod;
+
+    /// Get the number of elements in the buffer.
+    fn get_len(&self) -> usize;
+
+    /// Get the number of elements that fit in the buffer without
+    /// reallocating.
+    fn get_capacity(&self) -> usize;
+
+    /// Get whether the buffer holds no elements or not.
+    fn is_empty(&self) -> bool {
+        return self.get_len() == 0;
+    }
+
+    /// Buffer data to this space in GPU memory, replacing the previous
+    /// contents.
+    fn buffer_data(&mut self, data: &[Self::Element]);
+
+    /// Overwrite the elements starting at `offset`, which can not be past the
+    /// end of the buffer. Writing past the end extends the buffer.
+    fn update(&mut self, offset: usize, data: &[Self::Element]);
+
+    /// Buffer data to this space in GPU memory, returning a fence that is
+    /// signaled once the GPU is done with the upload.
+    #[track_caller]
+    fn buffer_data_fenced(&mut self, data: &[Self::Element]) -> Fence {
+        self.buffer_data(data);
+        return Fence::new(self.get_context());
+    }
+}
+
+/// Length and capacity of a typed buffer, which writes to the buffer bound
+/// to the given target.
+#[derive(Debug)]
+pub(crate) struct BufferStorage<T> {
+    _len: usize,
+    _capacity: usize,
+    _phantom: PhantomData<T>,
+}
+
+impl<T: Pod> BufferStorage<T> {
+    pub fn new() -> Self {
+        return BufferStorage {
+            _len: 0,
+            _capacity: 0,
+            _phantom: PhantomData,
+        };
+    }
+
+    pub fn get_len(&self) -> usize {
+        return self._len;
+    }
+
+    pub fn get_capacity(&self) -> usize {
+        return self._capacity;
+    }
+
+    /// Get the size in bytes of the storage.
+    pub fn get_size(&self) -> usize {
+        return self._capacity * size_of::<T>();
+    }
+
+    /// Replace the contents of the buffer, reusing the storage if the data
+    /// fits.
+    pub fn replace(&mut self, target: GLenum, usage: GLenum, data: &[T]) {
+        let size = std::mem::size_of_val(data);
+        if data.len() > self._capacity {
+            log::debug!("Buffer :: Allocating {:?} bytes", size);
+            unsafe {
+                gl_call!(BufferData(
+                    target,
+                    size as isize,
+                    data.as_ptr() as *const GLvoid,
+                    usage,
+                ));
+            }
+            self._capacity = data.len();
+        } else if !data.is_empty() {
+            unsafe {
+                gl_call!(BufferSubData(
+                    target,
+                    0,
+                    size as isize,
+                    data.as_ptr() as *const GLvoid,
+                ));
+            }
+        }
+        self._len = data.len();
+    }
+
+    /// Overwrite the elements starting at `offset`, growing the storage if
+    /// the data does not fit.
+    pub fn update(&mut self, target: GLenum, usage: GLenum, offset: usize, data: &[T]) {
+        assert!(
+            offset <= self._len,
+            "Buffer update at {} is past the end of the buffer ({})",
+            offset,
+            self._len
+        );
+        let end = offset + data.len();
+        if end > self._capacity {
+            self.grow(target, usage, end.max(2 * self._capacity));
+        }
+        if !data.is_empty() {
+            unsafe {
+                gl_call!(BufferSubData(
+                    target,
+                    (offset * size_of::<T>()) as isize,
+                    std::mem::size_of_val(data) as isize,
+                    data.as_ptr() as *const GLvoid,
+                ));
+            }
+        }
+        self._len = self._len.max(end);
+    }
+
+    /// Reallocate the storage with the given capacity, keeping the current
+    /// contents by copying them through a temporary buffer. The buffer keeps
+    /// its ID, so the vertex arrays using it stay valid.
+    fn grow(&mut self, target: GLenum, usage: GLenum, capacity: usize) {
+        log::debug!(
+            "Buffer :: Growing from {:?} to {:?} elements",
+            self._capacity,
+            capacity
+        );
+        let new_size = (capacity * size_of::<T>()) as isize;
+        let size = (self._len * size_of::<T>()) as isize;
+        if size == 0 {
+            unsafe {
+                gl_call!(BufferData(target, new_size, null(), usage));
+            }
+        } else {
+            let mut tmp = 0;
+            unsafe {
+                gl_call!(GenBuffers(1, &mut tmp));
+            }
+            StateCache::bind_buffer(gl::COPY_WRITE_BUFFER, tmp);
+            unsafe {
+                gl_call!(BufferData(
+                    gl::COPY_WRITE_BUFFER,
+                    size,
+                    null(),
+                    gl::STREAM_COPY
+                ));
+                gl_call!(CopyBufferSubData(target, gl::COPY_WRITE_BUFFER, 0, 0, size));
+                gl_call!(BufferData(target, new_size, null(), usage));
+                gl_call!(CopyBufferSubData(gl::COPY_WRITE_BUFFER, target, 0, 0, size));
+            }
+            StateCache::forget_buffer(tmp);
+            unsafe {
+                gl_call!(DeleteBuffers(1, &tmp));
+            }
+        }
+        self._capacity = capacity;
+    }
+}
+
+#[cfg(all(test, feature = "gl-mock"))]
+mod tests {
+    use super::*;
+    use crate::mock::take_call_strings;
+    use crate::{Bindable, HasID, MockGL, MockObject, VertexBuffer};
+
+    #[derive(Clone, Copy, Debug, Pod, Zeroable)]
+    #[repr(C)]
+    struct Vertex {
+        position: [f32; 3],
+        uv: [f32; 2],
+    }
+
+    #[test]
+    fn replace_allocates_only_when_the_data_does_not_fit() {
+        let context = MockGL::load();
+        let mut vbo = VertexBuffer::<Vertex>::new(&context);
+        let vertex = Vertex {
+            position: [0.0; 3],
+            uv: [0.0; 2],
+        };
+        vbo.buffer_data(&[vertex; 2]);
+        MockGL::take_calls();
+
+        vbo.buffer_data(&[vertex; 3]);
+        assert_eq!(
+            take_call_strings(),
+            vec!["BufferData(34962, 60, ptr, 35044)"]
+        );
+        vbo.buffer_data(&[vertex; 1]);
+        assert_eq!(
+            take_call_strings(),
+            vec!["BufferSubData(34962, 0, 20, ptr)"]
+        );
+        vbo.buffer_data(&[]);
+        assert!(take_call_strings().is_empty());
+        assert_eq!(vbo.get_len(), 0);
+        assert_eq!(vbo.get_capacity(), 3);
+        assert_eq!(context.get_resource_stats().get_total_bytes(), 60);
+    }
+
+    #[test]
+    fn update_grows_an_empty_buffer_without_copying() {
+        let context = MockGL::load();
+        let mut vbo = VertexBuffer::<u32>::new(&context);
+        vbo.bind();
+        MockGL::take_calls();
+
+        vbo.update(0, &[1, 2, 3]);
+        assert_eq!(
+            take_call_strings(),
+            vec![
+                "BufferData(34962, 12, null, 35044)",
+                "BufferSubData(34962, 0, 12, ptr)",
+            ]
+        );
+        assert_eq!(vbo.get_len(), 3);
+        assert_eq!(vbo.get_capacity(), 3);
+    }
+
+    #[test]
+    fn update_grows_through_the_copy_write_buffer() {
+        let context = MockGL::load();
+        let mut vbo = VertexBuffer::<u32>::new(&context);
+        vbo.buffer_data(&[1, 2, 3, 4]);
+        let id = vbo.get_id();
+        MockGL::take_calls();
+
+        vbo.update(4, &[5]);
+        let tmp = id + 1;
+        assert_eq!(
+            take_call_strings(),
+            vec![
+                "GenBuffers(1, ptr)".to_string(),
+                format!("BindBuffer(36663, {tmp})"),
+                "BufferData(36663, 16, null, 35042)".to_string(),
+                "CopyBufferSubData(34962, 36663, 0, 0, 16)".to_string(),
+                "BufferData(34962, 32, null, 35044)".to_string(),
+                "CopyBufferSubData(36663, 34962, 0, 0, 16)".to_string(),
+                "DeleteBuffers(1, ptr)".to_string(),
+                "BufferSubData(34962, 16, 4, ptr)".to_string(),
+            ]
+        );
+        assert!(!MockGL::is_alive(MockObject::Buffer, tmp));
+        assert_eq!(MockGL::get_buffer_binding(gl::ARRAY_BUFFER), id);
+        assert_eq!(vbo.get_len(), 5);
+        assert_eq!(vbo.get_capacity(), 8);
+    }
+
+    #[test]
+    fn update_doubles_the_capacity_when_appending() {
+        let context = MockGL::load();
+        let mut vbo = VertexBuffer::<f32>::new(&context);
+        let mut capacities = Vec::new();
+        for i in 0..9 {
+            vbo.update(i, &[i as f32]);
+            capacities.push(vbo.get_capacity());
+        }
+        assert_eq!(capacities, vec![1, 2, 4, 4, 8, 8, 8, 8, 16]);
+        assert_eq!(vbo.get_len(), 9);
+        assert_eq!(context.get_resource_stats().get_total_bytes(), 64);
+    }
+
+    #[test]
+    #[should_panic(expected = "past the end of the buffer")]
+    fn update_past_the_end_panics() {
+        let context = MockGL::load();
+        let mut vbo = VertexBuffer::<f32>::new(&context);
+        vbo.buffer_data(&[1.0, 2.0]);
+        vbo.update(3, &[3.0]);
+    }
 }
@@ -1,37 +1,63 @@
-use crate::{Bindable, Buffer, HasID};
-use std::mem::size_of;
+use crate::buffers::BufferStorage;
+use crate::{
+    Bindable, Buffer, GLContext, HasContext, HasID, Index, Labelable, ResourceKind, StateCache,
+};
 
 use gl;
 use gl::types::*;
 use log;
 
-/// Element buffer object, which contains triangle data stored in the GPU.
-#[derive(Clone, Copy, Debug)]
-pub struct ElementBuffer {
+/// Element buffer object, which contains triangle indices of type `I` stored
+/// in the GPU.
+#[derive(Debug)]
+pub struct ElementBuffer<I> {
     /// Unique ID associated to the object.
     _id: u32,
 
+    /// Context that created the object.
+    _context: GLContext,
+
+    /// Length and capacity of the data.
+    _storage: BufferStorage<I>,
+
     /// Usage of the data.
     pub usage: GLenum,
 }
 
-impl HasID for ElementBuffer {
+impl<I> HasID for ElementBuffer<I> {
     fn get_id(&self) -> u32 {
         return self._id;
     }
 }
 
-impl ElementBuffer {
-    /// Generate a new vertex buffer.
-    pub fn new() -> Self {
+impl<I> HasContext for ElementBuffer<I> {
+    fn get_context(&self) -> &GLContext {
+        return &self._context;
+    }
+}
+
+impl<I> Labelable for ElementBuffer<I> {
+    fn get_resource_kind(&self) -> ResourceKind {
+        return ResourceKind::Buffer;
+    }
+}
+
+impl<I: Index> ElementBuffer<I> {
+    /// Generate a new element buffer in the given context.
+    #[track_caller]
+    pub fn new(context: &GLContext) -> Self {
+        context.debug_assert_current("ElementBuffer");
         let mut ebo_id = 0;
         unsafe {
-            gl::GenBuffers(1, &mut ebo_id);
+            gl_call!(GenBuffers(1, &mut ebo_id));
         }
         log::info!("ElementBuffer :: Creating new ElementBuffer {:?}", ebo_id);
+        context.register_resource(ResourceKind::Buffer, ebo_id);
 
         return ElementBuffer {
             _id: ebo_id,
+            _context: context.clone(),
+            _storage: BufferStorage::new(),
             usage: gl::STATIC_DRAW,
         };
     }
@@ -42,43 +68,77 @@ impl ElementBuffer {
         self.usage = new_usage;
         return self;
     }
+
+    /// Record the size of the storage in the registry.
+    fn update_size(&self) {
+        self._context.set_resource_size(
+            ResourceKind::Buffer,
+            self.get_id(),
+            self._storage.get_size(),
+        );
+    }
 }
 
-impl Bindable for ElementBuffer {
+impl<I> Bindable for ElementBuffer<I> {
     fn bind(&self) {
+        self._context.debug_assert_current("ElementBuffer");
         log::trace!("ElementBuffer :: Binding");
-        unsafe {
-            gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, self.get_id());
-        }
+        StateCache::bind_buffer(gl::ELEMENT_ARRAY_BUFFER, self.get_id());
     }
 
     fn unbind(&self) {
+        self._context.debug_assert_current("ElementBuffer");
         log::trace!("ElementBuffer :: Unbinding");
-        unsafe {
-            gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, 0);
-        }
+        StateCache::bind_buffer(gl::ELEMENT_ARRAY_BUFFER, 0);
     }
+}
 
-    fn delete(&self) {
+impl<I> Drop for ElementBuffer<I> {
+    fn drop(&mut self) {
+        if !self._context.check_delete("ElementBuffer") {
+            return;
+        }
         log::info!("ElementBuffer :: Deleting");
+        StateCache::forget_buffer(self.get_id());
         unsafe {
-            gl::DeleteBuffers(1, &self.get_id());
+            gl_call!(DeleteBuffers(1, &self.get_id()));
         }
+        self._context
+            .unregister_resource(ResourceKind::Buffer, self.get_id());
     }
 }
 
-impl Buffer for ElementBuffer {
-    fn buffer_data<T>(&self, data: &[T]) {
-        let size = data.len() * size_of::<T>();
-        log::info!("ElementBuffer :: Buffering {:?} bytes to GPU", size);
+impl<I: Index> Buffer for ElementBuffer<I> {
+    type Element = I;
+
+    fn get_len(&self) -> usize {
+        return self._storage.get_len();
+    }
+
+    fn get_capacity(&self) -> usize {
+        return self._storage.get_capacity();
+    }
+
+    fn buffer_data(&mut self, data: &[I]) {
+        log::info!(
+            "ElementBuffer :: Buffering {:?} bytes to GPU",
+            std::mem::size_of_val(data)
+        );
         self.bind();
-        unsafe {
-            gl::BufferData(
-                gl::ELEMENT_ARRAY_BUFFER,
-                size as isize,
-                data.as_ptr() as *const GLvoid,
-                self.usage,
-            );
-        };
+        self._storage
+            .replace(gl::ELEMENT_ARRAY_BUFFER, self.usage, data);
+        self.update_size();
+    }
+
+    fn update(&mut self, offset: usize, data: &[I]) {
+        log::debug!(
+            "ElementBuffer :: Updating {:?} elements at {:?}",
+            data.len(),
+            offset
+        );
+        self.bind();
+        self._storage
+            .update(gl::ELEMENT_ARRAY_BUFFER, self.usage, offset, data);
+        self.update_size();
     }
 }
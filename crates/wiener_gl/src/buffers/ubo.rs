@@ -1,42 +1,64 @@
-use crate::{Bindable, Buffer, HasID};
-use std::mem::size_of;
+use crate::buffers::BufferStorage;
+use crate::{
+    Bindable, Buffer, GLContext, HasContext, HasID, Labelable, Pod, ResourceKind, StateCache,
+};
 
 use gl;
 use gl::types::*;
 use log;
 
-/// Uniform buffer object, which contains uniform data stored in the GPU.
-#[derive(Clone, Copy, Debug)]
-pub struct UniformBuffer {
+/// Uniform buffer object, which contains uniform data of type `T` stored in
+/// the GPU.
+#[derive(Debug)]
+pub struct UniformBuffer<T> {
     /// Unique ID associated to the object.
     _id: u32,
 
-    /// Size of the data.
-    pub size: u32,
+    /// Context that created the object.
+    _context: GLContext,
 
     /// Usage of the data.
     pub usage: GLenum,
+
+    /// Length and capacity of the data.
+    _storage: BufferStorage<T>,
 }
 
-impl HasID for UniformBuffer {
+impl<T> HasID for UniformBuffer<T> {
     fn get_id(&self) -> u32 {
         return self._id;
     }
 }
 
-impl UniformBuffer {
-    /// Generate a new uniform buffer.
-    pub fn new(size: u32) -> Self {
+impl<T> HasContext for UniformBuffer<T> {
+    fn get_context(&self) -> &GLContext {
+        return &self._context;
+    }
+}
+
+impl<T> Labelable for UniformBuffer<T> {
+    fn get_resource_kind(&self) -> ResourceKind {
+        return ResourceKind::Buffer;
+    }
+}
+
+impl<T: Pod> UniformBuffer<T> {
+    /// Generate a new uniform buffer in the given context.
+    #[track_caller]
+    pub fn new(context: &GLContext) -> Self {
+        context.debug_assert_current("UniformBuffer");
         let mut ubo_id = 0;
         unsafe {
-            gl::GenBuffers(1, &mut ubo_id);
+            gl_call!(GenBuffers(1, &mut ubo_id));
         }
         log::info!("UniformBuffer :: Creating new UniformBuffer {:?}", ubo_id);
+        context.register_resource(ResourceKind::Buffer, ubo_id);
 
         return UniformBuffer {
             _id: ubo_id,
-            size,
+            _context: context.clone(),
             usage: gl::STATIC_DRAW,
+            _storage: BufferStorage::new(),
         };
     }
 
@@ -50,46 +72,75 @@ impl UniformBuffer {
     /// Binds the uniform buffer to the given index in memory.
     pub fn bind_index(&self, index: u32) {
         log::trace!("UniformBuffer :: Binding index");
-        unsafe {
-            gl::BindBufferBase(gl::UNIFORM_BUFFER, index, self.get_id());
-        }
+        StateCache::bind_buffer_base(gl::UNIFORM_BUFFER, index, self.get_id());
+    }
+
+    /// Record the size of the storage in the registry.
+    fn update_size(&self) {
+        self._context.set_resource_size(
+            ResourceKind::Buffer,
+            self.get_id(),
+            self._storage.get_size(),
+        );
     }
 }
 
-impl Bindable for UniformBuffer {
+impl<T> Bindable for UniformBuffer<T> {
     fn bind(&self) {
+        self._context.debug_assert_current("UniformBuffer");
         log::trace!("UniformBuffer :: Binding");
-        unsafe {
-            gl::BindBuffer(gl::UNIFORM_BUFFER, self.get_id());
-        }
+        StateCache::bind_buffer(gl::UNIFORM_BUFFER, self.get_id());
     }
 
     fn unbind(&self) {
+        self._context.debug_assert_current("UniformBuffer");
         log::trace!("UniformBuffer :: Unbinding");
-        unsafe {
-            gl::BindBuffer(gl::UNIFORM_BUFFER, 0);
-        }
+        StateCache::bind_buffer(gl::UNIFORM_BUFFER, 0);
     }
+}
 
-    fn delete(&self) {
+impl<T> Drop for UniformBuffer<T> {
+    fn drop(&mut self) {
+        if !self._context.check_delete("UniformBuffer") {
+            return;
+        }
         log::info!("UniformBuffer :: Deleting");
+        StateCache::forget_buffer(self.get_id());
         unsafe {
-            gl::DeleteBuffers(1, &self.get_id());
+            gl_call!(DeleteBuffers(1, &self.get_id()));
         }
+        self._context
+            .unregister_resource(ResourceKind::Buffer, self.get_id());
     }
 }
 
-impl Buffer for UniformBuffer {
-    fn buffer_data<T>(&self, data: &[T]) {
+impl<T: Pod> Buffer for UniformBuffer<T> {
+    type Element = T;
+
+    fn get_len(&self) -> usize {
+        return self._storage.get_len();
+    }
+
+    fn get_capacity(&self) -> usize {
+        return self._storage.get_capacity();
+    }
+
+    fn buffer_data(&mut self, data: &[T]) {
         log::info!("UniformBuffer :: Buffering data to GPU");
         self.bind();
-        unsafe {
-            gl::BufferData(
-                gl::UNIFORM_BUFFER,
-                (data.len() * size_of::<T>()) as isize,
-                data.as_ptr() as *const GLvoid,
-                self.usage,
-            );
-        };
+        self._storage.replace(gl::UNIFORM_BUFFER, self.usage, data);
+        self.update_size();
+    }
+
+    fn update(&mut self, offset: usize, data: &[T]) {
+        log::debug!(
+            "UniformBuffer :: Updating {:?} elements at {:?}",
+            data.len(),
+            offset
+        );
+        self.bind();
+        self._storage
+            .update(gl::UNIFORM_BUFFER, self.usage, offset, data);
+        self.update_size();
     }
 }
@@ -1,4 +1,6 @@
-use crate::{Bindable, HasID};
+use crate::{
+    Bindable, DataType, GLContext, HasContext, HasID, Labelable, ResourceKind, StateCache,
+};
 
 use gl;
 use gl::types::*;
@@ -14,11 +16,11 @@ pub struct VertexAttribute {
     pub size: u32,
 
     /// Data type of the attribute.
-    pub data_type: GLenum,
+    pub data_type: DataType,
 }
 
 impl VertexAttribute {
-    pub fn new(location: usize, size: u32, data_type: GLenum) -> Self {
+    pub fn new(location: usize, size: u32, data_type: DataType) -> Self {
         return VertexAttribute {
             location,
             size,
@@ -29,10 +31,10 @@ impl VertexAttribute {
     /// Bind the vertex attribute to a given VAO.
     pub fn bind_vao(&self, vao: &VertexArray) {
         unsafe {
-            gl::VertexAttribPointer(
+            gl_call!(VertexAttribPointer(
                 self.location as u32,
                 self.size as i32,
-                self.data_type,
+                self.data_type as GLenum,
                 gl::FALSE,
                 (vao.size * vao.stride) as i32,
                 (vao.size
@@ -40,8 +42,8 @@ impl VertexAttribute {
                         .iter()
                         .map(|a| a.size)
                         .sum::<u32>()) as *const _,
-            );
-            gl::EnableVertexArrayAttrib(vao.get_id(), self.location as u32);
+            ));
+            gl_call!(EnableVertexArrayAttrib(vao.get_id(), self.location as u32));
         }
     }
 }
@@ -51,17 +53,20 @@ impl Default for VertexAttribute {
         return VertexAttribute {
             location: 0,
             size: 0,
-            data_type: gl::FLOAT,
+            data_type: DataType::Float,
         };
     }
 }
 
 /// Vertex array that specifies the vertex layout on GPU memory.
-#[derive(Clone, Copy, Debug)]
+#[derive(Debug)]
 pub struct VertexArray<'a> {
     /// Unique ID associated to the object.
     _id: u32,
 
+    /// Context that created the object.
+    _context: GLContext,
+
     /// Number of elements in each vertex.
     pub stride: u32,
 
@@ -78,7 +83,39 @@ impl<'a> HasID for VertexArray<'a> {
     }
 }
 
+impl<'a> HasContext for VertexArray<'a> {
+    fn get_context(&self) -> &GLContext {
+        return &self._context;
+    }
+}
+
+impl<'a> Labelable for VertexArray<'a> {
+    fn get_resource_kind(&self) -> ResourceKind {
+        return ResourceKind::VertexArray;
+    }
+}
+
 impl<'a> VertexArray<'a> {
+    /// Generate a builder for a vertex array in the given context.
+    #[track_caller]
+    pub fn new(context: &GLContext) -> Self {
+        context.debug_assert_exactly_current("VertexArray");
+        let mut vao_id = 0;
+        unsafe {
+            gl_call!(GenVertexArrays(1, &mut vao_id));
+        }
+        log::info!("VertexArray :: Creating new VertexArray {:?}", vao_id);
+        context.register_resource(ResourceKind::VertexArray, vao_id);
+
+        return VertexArray {
+            _id: vao_id,
+            _context: context.clone(),
+            stride: 0,
+            size: 4,
+            layout: &[],
+        };
+    }
+
     /// Set the size in bytes of each number.
     pub fn size(mut self, new_size: u32) -> Self {
         self.size = new_size;
@@ -123,41 +160,29 @@ impl<'a> VertexArray<'a> {
 
 impl<'a> Bindable for VertexArray<'a> {
     fn bind(&self) {
+        self._context.debug_assert_exactly_current("VertexArray");
         log::trace!("VertexArray :: Binding");
-        unsafe {
-            gl::BindVertexArray(self.get_id());
-        }
+        StateCache::bind_vertex_array(self.get_id());
     }
 
     fn unbind(&self) {
+        self._context.debug_assert_exactly_current("VertexArray");
         log::trace!("VertexArray :: Unbinding");
-        unsafe {
-            gl::BindVertexArray(0);
-        }
-    }
-
-    fn delete(&self) {
-        log::info!("VertexArray :: Deleting");
-        unsafe {
-            gl::DeleteVertexArrays(1, &self.get_id());
-        }
+        StateCache::bind_vertex_array(0);
     }
 }
 
-impl<'a> Default for VertexArray<'a> {
-    /// Generate a builder for a vertex array.
-    fn default() -> Self {
-        let mut vao_id = 0;
+impl<'a> Drop for VertexArray<'a> {
+    fn drop(&mut self) {
+        if !self._context.check_delete_exactly("VertexArray") {
+            return;
+        }
+        log::info!("VertexArray :: Deleting");
+        StateCache::forget_vertex_array(self.get_id());
         unsafe {
-            gl::GenVertexArrays(1, &mut vao_id);
+            gl_call!(DeleteVertexArrays(1, &self.get_id()));
         }
-        log::info!("VertexArray :: Creating new VertexArray {:?}", vao_id);
-
-        return VertexArray {
-            _id: vao_id,
-            stride: 0,
-            size: 4,
-            layout: &[],
-        };
+        self._context
+            .unregister_resource(ResourceKind::VertexArray, self.get_id());
     }
 }
@@ -1,36 +1,62 @@
-use crate::{Bindable, Buffer, HasID};
-use std::mem::size_of;
+use crate::buffers::BufferStorage;
+use crate::{
+    Bindable, Buffer, GLContext, HasContext, HasID, Labelable, Pod, ResourceKind, StateCache,
+};
 
 use gl;
 use gl::types::*;
 
-/// Vertex buffer object, which contains vertex data stored in the GPU.
-#[derive(Clone, Copy, Debug)]
-pub struct VertexBuffer {
+/// Vertex buffer object, which contains vertex data of type `T` stored in the
+/// GPU.
+#[derive(Debug)]
+pub struct VertexBuffer<T> {
     /// Unique ID associated to the object.
     _id: u32,
 
+    /// Context that created the object.
+    _context: GLContext,
+
+    /// Length and capacity of the data.
+    _storage: BufferStorage<T>,
+
     /// Usage of the data.
     pub usage: GLenum,
 }
 
-impl HasID for VertexBuffer {
+impl<T> HasID for VertexBuffer<T> {
     fn get_id(&self) -> u32 {
         return self._id;
     }
 }
 
-impl VertexBuffer {
-    /// Generate a new vertex buffer.
-    pub fn new() -> Self {
+impl<T> HasContext for VertexBuffer<T> {
+    fn get_context(&self) -> &GLContext {
+        return &self._context;
+    }
+}
+
+impl<T> Labelable for VertexBuffer<T> {
+    fn get_resource_kind(&self) -> ResourceKind {
+        return ResourceKind::Buffer;
+    }
+}
+
+impl<T: Pod> VertexBuffer<T> {
+    /// Generate a new vertex buffer in the given context.
+    #[track_caller]
+    pub fn new(context: &GLContext) -> Self {
+        context.debug_assert_current("VertexBuffer");
         let mut vbo_id = 0;
         unsafe {
-            gl::GenBuffers(1, &mut vbo_id);
+            gl_call!(GenBuffers(1, &mut vbo_id));
         }
         log::info!("VertexBuffer :: Creating new VertexBuffer {:?}", vbo_id);
+        context.register_resource(ResourceKind::Buffer, vbo_id);
 
         return VertexBuffer {
             _id: vbo_id,
+            _context: context.clone(),
+            _storage: BufferStorage::new(),
             usage: gl::STATIC_DRAW,
         };
     }
@@ -41,43 +67,76 @@ impl VertexBuffer {
         self.usage = new_usage;
         return self;
     }
+
+    /// Record the size of the storage in the registry.
+    fn update_size(&self) {
+        self._context.set_resource_size(
+            ResourceKind::Buffer,
+            self.get_id(),
+            self._storage.get_size(),
+        );
+    }
 }
 
-impl Bindable for VertexBuffer {
+impl<T> Bindable for VertexBuffer<T> {
     fn bind(&self) {
+        self._context.debug_assert_current("VertexBuffer");
         log::trace!("VertexBuffer :: Binding");
-        unsafe {
-            gl::BindBuffer(gl::ARRAY_BUFFER, self.get_id());
-        }
+        StateCache::bind_buffer(gl::ARRAY_BUFFER, self.get_id());
     }
 
     fn unbind(&self) {
+        self._context.debug_assert_current("VertexBuffer");
         log::trace!("VertexBuffer :: Unbinding");
-        unsafe {
-            gl::BindBuffer(gl::ARRAY_BUFFER, 0);
-        }
+        StateCache::bind_buffer(gl::ARRAY_BUFFER, 0);
     }
+}
 
-    fn delete(&self) {
+impl<T> Drop for VertexBuffer<T> {
+    fn drop(&mut self) {
+        if !self._context.check_delete("VertexBuffer") {
+            return;
+        }
         log::info!("VertexBuffer :: Deleting");
+        StateCache::forget_buffer(self.get_id());
         unsafe {
-            gl::DeleteBuffers(1, &self.get_id());
+            gl_call!(DeleteBuffers(1, &self.get_id()));
         }
+        self._context
+            .unregister_resource(ResourceKind::Buffer, self.get_id());
     }
 }
 
-impl Buffer for VertexBuffer {
-    fn buffer_data<T>(&self, data: &[T]) {
-        let size = data.len() * size_of::<T>();
-        log::info!("VertexBuffer :: Buffering {:?} bytes to GPU", size);
+impl<T: Pod> Buffer for VertexBuffer<T> {
+    type Element = T;
+
+    fn get_len(&self) -> usize {
+        return self._storage.get_len();
+    }
+
+    fn get_capacity(&self) -> usize {
+        return self._storage.get_capacity();
+    }
+
+    fn buffer_data(&mut self, data: &[T]) {
+        log::info!(
+            "VertexBuffer :: Buffering {:?} bytes to GPU",
+            std::mem::size_of_val(data)
+        );
         self.bind();
-        unsafe {
-            gl::BufferData(
-                gl::ARRAY_BUFFER,
-                size as isize,
-                data.as_ptr() as *const GLvoid,
-                self.usage,
-            );
-        };
+        self._storage.replace(gl::ARRAY_BUFFER, self.usage, data);
+        self.update_size();
+    }
+
+    fn update(&mut self, offset: usize, data: &[T]) {
+        log::debug!(
+            "VertexBuffer :: Updating {:?} elements at {:?}",
+            data.len(),
+            offset
+        );
+        self.bind();
+        self._storage
+            .update(gl::ARRAY_BUFFER, self.usage, offset, data);
+        self.update_size();
     }
 }
@@ -0,0 +1,134 @@
+use crate::GLContext;
+
+use gl;
+use gl::types::*;
+use log;
+use std::ffi::CStr;
+use std::rc::Rc;
+
+fn get_string(name: GLenum) -> String {
+    unsafe {
+        let ptr = gl_call!(GetString(name));
+        if ptr.is_null() {
+            return String::new();
+        }
+        return CStr::from_ptr(ptr as *const _)
+            .to_string_lossy()
+            .into_owned();
+    }
+}
+
+fn get_integer(name: GLenum) -> i32 {
+    let mut value = 0;
+    unsafe {
+        gl_call!(GetIntegerv(name, &mut value));
+    }
+    return value;
+}
+
+/// Capabilities and limits of the OpenGL implementation.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GLCapabilities {
+    /// Version string reported by the driver.
+    pub version: String,
+
+    /// Name of the renderer, usually the GPU.
+    pub renderer: String,
+
+    /// Company responsible for the implementation.
+    pub vendor: String,
+
+    /// Every supported extension.
+    pub extensions: Vec<String>,
+
+    /// Largest width or height of a texture.
+    pub max_texture_size: i32,
+
+    /// Largest number of samples of a multisampled buffer.
+    pub max_samples: i32,
+
+    /// Number of texture units that can be used at the same time.
+    pub max_texture_units: i32,
+
+    /// Largest size in bytes of a uniform block.
+    pub max_uniform_block_size: i32,
+
+    /// Number of vertex attributes available to a vertex shader.
+    pub max_vertex_attribs: i32,
+
+    /// Number of buffers a fragment shader can draw to.
+    pub max_draw_buffers: i32,
+}
+
+impl GLCapabilities {
+    /// Query the capabilities of the current context.
+    pub fn query() -> Self {
+        log::info!("GLCapabilities :: Querying capabilities");
+        let num_extensions = get_integer(gl::NUM_EXTENSIONS);
+        let extensions = (0..num_extensions as u32)
+            .filter_map(|i| unsafe {
+                let ptr = gl_call!(GetStringi(gl::EXTENSIONS, i));
+                if ptr.is_null() {
+                    return None;
+                }
+                return Some(
+                    CStr::from_ptr(ptr as *const _)
+                        .to_string_lossy()
+                        .into_owned(),
+                );
+            })
+            .collect();
+        let capabilities = GLCapabilities {
+            version: get_string(gl::VERSION),
+            renderer: get_string(gl::RENDERER),
+            vendor: get_string(gl::VENDOR),
+            extensions,
+            max_texture_size: get_integer(gl::MAX_TEXTURE_SIZE),
+            max_samples: get_integer(gl::MAX_SAMPLES),
+            max_texture_units: get_integer(gl::MAX_COMBINED_TEXTURE_IMAGE_UNITS),
+            max_uniform_block_size: get_integer(gl::MAX_UNIFORM_BLOCK_SIZE),
+            max_vertex_attribs: get_integer(gl::MAX_VERTEX_ATTRIBS),
+            max_draw_buffers: get_integer(gl::MAX_DRAW_BUFFERS),
+        };
+        log::info!(
+            "GLCapabilities :: Running OpenGL {} on {} ({})",
+            capabilities.version,
+            capabilities.renderer,
+            capabilities.vendor
+        );
+        return capabilities;
+    }
+
+    /// Get the capabilities of the current context, if they have been
+    /// queried.
+    pub fn get_current() -> Option<Rc<GLCapabilities>> {
+        return GLContext::current().and_then(|c| c.get_capabilities());
+    }
+
+    /// Get whether an extension is supported or not.
+    pub fn has_extension(&self, name: &str) -> bool {
+        return self.extensions.iter().any(|e| e == name);
+    }
+
+    /// Clamp a number of samples to the supported maximum.
+    pub fn clamp_samples(&self, samples: i32) -> i32 {
+        if samples > self.max_samples {
+            log::warn!(
+                "GLCapabilities :: {:?} samples requested, but at most {:?} are supported",
+                samples,
+                self.max_samples
+            );
+            return self.max_samples;
+        }
+        return samples;
+    }
+}
+
+/// Clamp a number of samples against the current capabilities, if they have
+/// been queried.
+pub(crate) fn clamp_samples_to_current(samples: i32) -> i32 {
+    return match GLCapabilities::get_current() {
+        Some(capabilities) => capabilities.clamp_samples(samples),
+        None => samples,
+    };
+}
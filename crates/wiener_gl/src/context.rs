@@ -0,0 +1,384 @@
+use crate::registry::ResourceRegistry;
+use crate::{GLCapabilities, ResourceInfo, ResourceKind, ResourceStats};
+
+use gl::types::*;
+use log;
+use std::cell::{Cell, RefCell};
+use std::panic::Location;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Last id given to a context.
+static LAST_ID: AtomicU64 = AtomicU64::new(0);
+
+thread_local! {
+    /// Context current on this thread.
+    static CURRENT: RefCell<Option<Rc<ContextInfo>>> = const { RefCell::new(None) };
+}
+
+/// Contexts that share their objects, which live until every context of the
+/// group has been destroyed, along with the registry of those objects.
+#[derive(Debug)]
+struct ShareGroup {
+    id: u64,
+    live_contexts: Cell<usize>,
+    registry: RefCell<ResourceRegistry>,
+}
+
+#[derive(Debug)]
+struct ContextInfo {
+    id: u64,
+    share_group: Rc<ShareGroup>,
+    destroyed: Cell<bool>,
+    debug_output: Cell<bool>,
+    capabilities: RefCell<Option<Rc<GLCapabilities>>>,
+
+    /// Registry of the objects that are not shared with the group.
+    registry: RefCell<ResourceRegistry>,
+}
+
+/// Handle to an OpenGL context, required to create resources.
+///
+/// Every window owns a context (see `GLWindow::get_context`), and resources
+/// remember the context that created them. In debug builds, using or
+/// dropping a resource while neither its context nor one sharing its objects
+/// is current panics. The handle can not be sent to other threads, so
+/// neither can the resources.
+///
+/// Resources should be dropped before the window (or headless context) that
+/// owns their context. The objects of a context are destroyed along with it,
+/// so resources that outlive every context sharing their objects are not
+/// deleted again when dropped, and are instead logged as leaks when the
+/// window is dropped.
+///
+/// Each context keeps a registry of the buffers, textures, renderbuffers,
+/// framebuffers, vertex arrays, shaders, shader programs, queries and fences
+/// it created, with their estimated size, debug name and creation site. The
+/// objects that are shared (see `ResourceKind::is_shared`) are recorded once
+/// for the whole group of contexts sharing them.
+#[derive(Clone, Debug)]
+pub struct GLContext {
+    _info: Rc<ContextInfo>,
+}
+
+impl GLContext {
+    fn with_share_group(share_group: Option<Rc<ShareGroup>>) -> Self {
+        let id = LAST_ID.fetch_add(1, Ordering::Relaxed) + 1;
+        log::info!("GLContext :: Creating context {:?}", id);
+        let share_group = share_group.unwrap_or_else(|| {
+            Rc::new(ShareGroup {
+                id,
+                live_contexts: Cell::new(0),
+                registry: RefCell::new(ResourceRegistry::default()),
+            })
+        });
+        share_group
+            .live_contexts
+            .set(share_group.live_contexts.get() + 1);
+        return GLContext {
+            _info: Rc::new(ContextInfo {
+                id,
+                share_group,
+                destroyed: Cell::new(false),
+                debug_output: Cell::new(false),
+                capabilities: RefCell::new(None),
+                registry: RefCell::new(ResourceRegistry::default()),
+            }),
+        };
+    }
+
+    /// Create a handle for a new context.
+    pub(crate) fn new() -> Self {
+        return GLContext::with_share_group(None);
+    }
+
+    /// Create a handle for a new context that shares objects with this one.
+    pub(crate) fn new_shared(&self) -> Self {
+        return GLContext::with_share_group(Some(self._info.share_group.clone()));
+    }
+
+    /// Record that the context has been destroyed along with its window, so
+    /// the resources that outlive it are not deleted.
+    pub(crate) fn destroy(&self) {
+        if !self._info.destroyed.replace(true) {
+            log::info!("GLContext :: Destroying context {:?}", self._info.id);
+            let group = &self._info.share_group;
+            group.live_contexts.set(group.live_contexts.get() - 1);
+        }
+        self.release_current();
+    }
+
+    /// Get whether the context has been destroyed or not.
+    pub fn is_destroyed(&self) -> bool {
+        return self._info.destroyed.get();
+    }
+
+    /// Record this context as the current one on this thread.
+    pub(crate) fn set_current(&self) {
+        CURRENT.with(|c| *c.borrow_mut() = Some(self._info.clone()));
+    }
+
+    /// Record that no context is current on this thread, if this one was.
+    pub(crate) fn release_current(&self) {
+        if self.is_exactly_current() {
+            CURRENT.with(|c| *c.borrow_mut() = None);
+        }
+    }
+
+    /// Get the context current on this thread, if any.
+    pub fn current() -> Option<GLContext> {
+        return CURRENT.with(|c| c.borrow().clone().map(|info| GLContext { _info: info }));
+    }
+
+    /// Get whether debug output has been enabled in this context or not
+    /// (see `enable_debug_output`).
+    pub fn has_debug_output(&self) -> bool {
+        return self._info.debug_output.get();
+    }
+
+    /// Record whether debug output is enabled in this context.
+    pub(crate) fn set_debug_output(&self, enabled: bool) {
+        self._info.debug_output.set(enabled);
+    }
+
+    /// Get the capabilities of the OpenGL implementation behind this context,
+    /// if they have been queried.
+    pub fn get_capabilities(&self) -> Option<Rc<GLCapabilities>> {
+        return self._info.capabilities.borrow().clone();
+    }
+
+    /// Record the capabilities of the OpenGL implementation behind this
+    /// context.
+    pub(crate) fn set_capabilities(&self, capabilities: Rc<GLCapabilities>) {
+        *self._info.capabilities.borrow_mut() = Some(capabilities);
+    }
+
+    /// Get the unique id of the context.
+    pub fn get_id(&self) -> u64 {
+        return self._info.id;
+    }
+
+    /// Get whether this context is the current one or not.
+    pub fn is_exactly_current(&self) -> bool {
+        return CURRENT.with(|c| {
+            c.borrow()
+                .as_ref()
+                .is_some_and(|info| info.id == self._info.id)
+        });
+    }
+
+    /// Get whether the current context is this one or shares objects with
+    /// it, so the resources created by this context can be used.
+    pub fn is_current(&self) -> bool {
+        return CURRENT.with(|c| {
+            c.borrow()
+                .as_ref()
+                .is_some_and(|info| info.share_group.id == self._info.share_group.id)
+        });
+    }
+
+    /// Panic in debug builds if the resources of this context can not be
+    /// used, naming the offending resource. Nothing is checked while already
+    /// panicking, so resources can be dropped during unwinding.
+    pub(crate) fn debug_assert_current(&self, resource: &str) {
+        debug_assert!(
+            self.is_current() || std::thread::panicking(),
+            "{} of context {:?} used while the context is not current",
+            resource,
+            self._info.id
+        );
+    }
+
+    /// Panic in debug builds if this context is not the current one, for
+    /// objects that are not shared between contexts, such as vertex arrays,
+    /// framebuffers and queries.
+    pub(crate) fn debug_assert_exactly_current(&self, resource: &str) {
+        debug_assert!(
+            self.is_exactly_current() || std::thread::panicking(),
+            "{} of context {:?} used in another context",
+            resource,
+            self._info.id
+        );
+    }
+
+    /// Check whether a resource of this context can be deleted, which panics
+    /// in debug builds if its context is not current (see
+    /// `debug_assert_current`). Returns `false` if every context sharing the
+    /// objects has been destroyed, as the object is already gone.
+    pub(crate) fn check_delete(&self, resource: &str) -> bool {
+        if self._info.share_group.live_contexts.get() == 0 {
+            log::warn!(
+                "{} :: Not deleted, since context {:?} has been destroyed",
+                resource,
+                self._info.id
+            );
+            return false;
+        }
+        self.debug_assert_current(resource);
+        return true;
+    }
+
+    /// Check whether a resource that is not shared between contexts can be
+    /// deleted, which panics in debug builds if this context is not the
+    /// current one. Returns `false` if the context has been destroyed.
+    pub(crate) fn check_delete_exactly(&self, resource: &str) -> bool {
+        if self.is_destroyed() {
+            log::warn!(
+                "{} :: Not deleted, since context {:?} has been destroyed",
+                resource,
+                self._info.id
+            );
+            return false;
+        }
+        self.debug_assert_exactly_current(resource);
+        return true;
+    }
+
+    /// Get the registry where resources of a kind are recorded.
+    fn get_registry(&self, kind: ResourceKind) -> &RefCell<ResourceRegistry> {
+        return if kind.is_shared() {
+            &self._info.share_group.registry
+        } else {
+            &self._info.registry
+        };
+    }
+
+    /// Record a new resource in the registry, created by the caller.
+    #[track_caller]
+    pub(crate) fn register_resource(&self, kind: ResourceKind, id: u32) {
+        self.get_registry(kind)
+            .borrow_mut()
+            .register(kind, id, Location::caller());
+    }
+
+    /// Remove a deleted resource from the registry.
+    pub(crate) fn unregister_resource(&self, kind: ResourceKind, id: u32) {
+        self.get_registry(kind).borrow_mut().unregister(kind, id);
+    }
+
+    /// Record the estimated size in bytes of a resource.
+    pub(crate) fn set_resource_size(&self, kind: ResourceKind, id: u32, size: usize) {
+        self.get_registry(kind)
+            .borrow_mut()
+            .set_size(kind, id, size);
+    }
+
+    /// Record the debug name of a resource.
+    pub(crate) fn set_resource_label(&self, kind: ResourceKind, id: u32, label: &str) {
+        self.get_registry(kind)
+            .borrow_mut()
+            .set_label(kind, id, label);
+    }
+
+    /// Get every resource that is still alive and can be used by this
+    /// context, i.e. the ones it created and the shared ones created by any
+    /// context of its group, sorted by kind and id.
+    pub fn get_resources(&self) -> Vec<ResourceInfo> {
+        let mut resources = self._info.registry.borrow().get_resources();
+        resources.extend(self._info.share_group.registry.borrow().get_resources());
+        resources.sort_by_key(|r| (r.kind as GLenum, r.id));
+        return resources;
+    }
+
+    /// Get the number and estimated size of the resources that are still
+    /// alive and can be used by this context.
+    pub fn get_resource_stats(&self) -> ResourceStats {
+        return ResourceStats::from_resources(&self.get_resources());
+    }
+
+    /// Get whether another context sharing objects with this one is still
+    /// alive or not.
+    fn has_live_siblings(&self) -> bool {
+        let own = if self.is_destroyed() { 0 } else { 1 };
+        return self._info.share_group.live_contexts.get() > own;
+    }
+
+    /// Log every resource created by this context that is still alive,
+    /// returning how many there are. Called when the window of the context is
+    /// dropped, when every resource should have been dropped already, so the
+    /// resources logged here outlive their context.
+    ///
+    /// Shared resources are only logged when no other context of the group
+    /// is alive, since they can still be used until then.
+    pub fn log_leaks(&self) -> usize {
+        let mut resources = self._info.registry.borrow().get_resources();
+        if !self.has_live_siblings() {
+            resources.extend(self._info.share_group.registry.borrow().get_resources());
+        }
+        if resources.is_empty() {
+            log::debug!(
+                "GLContext :: No resources leaked by context {:?}",
+                self.get_id()
+            );
+            return 0;
+        }
+        log::warn!(
+            "GLContext :: {:?} resources of context {:?} are still alive",
+            resources.len(),
+            self.get_id()
+        );
+        for info in &resources {
+            log::warn!("GLContext :: Leaked {}", info);
+        }
+        return resources.len();
+    }
+}
+
+#[cfg(all(test, feature = "gl-mock"))]
+mod tests {
+    use super::*;
+    use crate::mock::take_call_strings;
+    use crate::{HasID, MockGL, MockObject, VertexArray, VertexBuffer};
+
+    #[test]
+    fn resources_outliving_their_context_are_not_deleted() {
+        let context = MockGL::load();
+        let vbo = VertexBuffer::<f32>::new(&context);
+        let vao = VertexArray::new(&context);
+        context.destroy();
+        assert!(context.is_destroyed());
+        assert!(GLContext::current().is_none());
+        MockGL::take_calls();
+
+        drop(vbo);
+        drop(vao);
+        assert!(take_call_strings().is_empty());
+        assert_eq!(context.get_resource_stats().get_total_count(), 2);
+    }
+
+    #[test]
+    fn shared_resources_are_deleted_while_a_context_of_the_group_lives() {
+        let context = MockGL::load();
+        let shared = context.new_shared();
+        let vbo = VertexBuffer::<f32>::new(&context);
+        let vao = VertexArray::new(&context);
+        context.destroy();
+        shared.set_current();
+        MockGL::take_calls();
+
+        // Vertex arrays are not shared, so they are gone with their context
+        let vao_id = vao.get_id();
+        drop(vao);
+        assert!(take_call_strings().is_empty());
+        assert!(MockGL::is_alive(MockObject::VertexArray, vao_id));
+        let id = vbo.get_id();
+        drop(vbo);
+        assert!(!MockGL::is_alive(MockObject::Buffer, id));
+        assert_eq!(context.get_resource_stats().get_total_count(), 1);
+    }
+
+    #[test]
+    fn shared_resources_are_not_leaked_while_a_context_of_the_group_lives() {
+        let context = MockGL::load();
+        let shared = context.new_shared();
+        let vbo = VertexBuffer::<f32>::new(&context);
+        context.destroy();
+        shared.set_current();
+
+        // The buffer can still be used by the shared context
+        assert_eq!(context.log_leaks(), 0);
+        assert_eq!(shared.log_leaks(), 1);
+        drop(vbo);
+        assert_eq!(shared.log_leaks(), 0);
+    }
+}
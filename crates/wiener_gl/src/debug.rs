@@ -0,0 +1,209 @@
+use crate::GLContext;
+
+use gl;
+use gl::types::*;
+use log;
+use std::ffi::{c_void, CStr};
+
+/// Call an OpenGL function. With the `gl-error-check` feature, the errors
+/// raised by the call are logged right after it, unless debug output of the
+/// current context is already reporting them.
+macro_rules! gl_call {
+    ($name:ident($($arg:expr),* $(,)?)) => {{
+        let result = gl::$name($($arg),*);
+        #[cfg(feature = "gl-error-check")]
+        $crate::check_gl_errors(stringify!($name));
+        result
+    }};
+}
+
+fn get_source_name(source: GLenum) -> &'static str {
+    return match source {
+        gl::DEBUG_SOURCE_API => "API",
+        gl::DEBUG_SOURCE_WINDOW_SYSTEM => "Window system",
+        gl::DEBUG_SOURCE_SHADER_COMPILER => "Shader compiler",
+        gl::DEBUG_SOURCE_THIRD_PARTY => "Third party",
+        gl::DEBUG_SOURCE_APPLICATION => "Application",
+        _ => "Other",
+    };
+}
+
+fn get_type_name(gltype: GLenum) -> &'static str {
+    return match gltype {
+        gl::DEBUG_TYPE_ERROR => "Error",
+        gl::DEBUG_TYPE_DEPRECATED_BEHAVIOR => "Deprecated behavior",
+        gl::DEBUG_TYPE_UNDEFINED_BEHAVIOR => "Undefined behavior",
+        gl::DEBUG_TYPE_PORTABILITY => "Portability",
+        gl::DEBUG_TYPE_PERFORMANCE => "Performance",
+        gl::DEBUG_TYPE_MARKER => "Marker",
+        gl::DEBUG_TYPE_PUSH_GROUP => "Push group",
+        gl::DEBUG_TYPE_POP_GROUP => "Pop group",
+        _ => "Other",
+    };
+}
+
+fn get_error_name(error: GLenum) -> &'static str {
+    return match error {
+        gl::INVALID_ENUM => "GL_INVALID_ENUM",
+        gl::INVALID_VALUE => "GL_INVALID_VALUE",
+        gl::INVALID_OPERATION => "GL_INVALID_OPERATION",
+        gl::INVALID_FRAMEBUFFER_OPERATION => "GL_INVALID_FRAMEBUFFER_OPERATION",
+        gl::OUT_OF_MEMORY => "GL_OUT_OF_MEMORY",
+        gl::STACK_UNDERFLOW => "GL_STACK_UNDERFLOW",
+        gl::STACK_OVERFLOW => "GL_STACK_OVERFLOW",
+        _ => "Unknown error",
+    };
+}
+
+extern "system" fn debug_callback(
+    source: GLenum,
+    gltype: GLenum,
+    id: GLuint,
+    severity: GLenum,
+    length: GLsizei,
+    message: *const GLchar,
+    _user_param: *mut c_void,
+) {
+    let message = unsafe {
+        if length < 0 {
+            CStr::from_ptr(message).to_string_lossy()
+        } else {
+            String::from_utf8_lossy(std::slice::from_raw_parts(
+                message as *const u8,
+                length as usize,
+            ))
+        }
+    };
+    let level = match severity {
+        gl::DEBUG_SEVERITY_HIGH => log::Level::Error,
+        gl::DEBUG_SEVERITY_MEDIUM => log::Level::Warn,
+        gl::DEBUG_SEVERITY_LOW => log::Level::Info,
+        _ => log::Level::Debug,
+    };
+    log::log!(
+        level,
+        "GL :: [{}] {} {}: {}",
+        get_source_name(source),
+        get_type_name(gltype),
+        id,
+        message.trim_end()
+    );
+}
+
+/// Install a debug message callback in the context, which must be current,
+/// forwarding every message to `log` with a level given by its severity.
+///
+/// Returns whether debug output was enabled or not, which requires a debug
+/// context (see `WindowDescriptor::set_debug_context`) supporting KHR_debug.
+pub fn enable_debug_output(context: &GLContext) -> bool {
+    context.debug_assert_exactly_current("Debug output");
+    if !gl::DebugMessageCallback::is_loaded() {
+        log::warn!("enable_debug_output :: KHR_debug is not supported");
+        return false;
+    }
+    let mut flags = 0;
+    unsafe {
+        gl::GetIntegerv(gl::CONTEXT_FLAGS, &mut flags);
+    }
+    if flags as GLenum & gl::CONTEXT_FLAG_DEBUG_BIT == 0 {
+        log::warn!("enable_debug_output :: The current context is not a debug context");
+        return false;
+    }
+
+    log::info!("enable_debug_output :: Installing debug message callback");
+    unsafe {
+        gl::Enable(gl::DEBUG_OUTPUT);
+        gl::Enable(gl::DEBUG_OUTPUT_SYNCHRONOUS);
+        gl::DebugMessageCallback(Some(debug_callback), std::ptr::null());
+        gl::DebugMessageControl(
+            gl::DONT_CARE,
+            gl::DONT_CARE,
+            gl::DONT_CARE,
+            0,
+            std::ptr::null(),
+            gl::TRUE,
+        );
+    }
+    context.set_debug_output(true);
+    return true;
+}
+
+/// Log every error raised since the last check, attributing them to the
+/// given call. Returns whether any error was raised or not.
+///
+/// Does nothing if debug output is enabled in the current context, since the
+/// debug callback already reports the errors.
+pub fn check_gl_errors(call: &str) -> bool {
+    if GLContext::current().is_some_and(|c| c.has_debug_output()) {
+        return false;
+    }
+    let mut raised = false;
+    loop {
+        let error = unsafe { gl::GetError() };
+        if error == gl::NO_ERROR {
+            return raised;
+        }
+        log::error!("GL :: {} raised {}", call, get_error_name(error));
+        raised = true;
+    }
+}
+
+/// Give a name to an OpenGL object, shown by debugging tools such as
+/// RenderDoc or apitrace. Does nothing if KHR_debug is not supported.
+pub fn set_object_label(identifier: GLenum, id: u32, label: &str) {
+    if !gl::ObjectLabel::is_loaded() {
+        return;
+    }
+    log::trace!(
+        "set_object_label :: Labeling object {:?} as {:?}",
+        id,
+        label
+    );
+    unsafe {
+        gl_call!(ObjectLabel(
+            identifier,
+            id,
+            label.len() as GLsizei,
+            label.as_ptr() as *const GLchar
+        ));
+    }
+}
+
+/// Debug group shown by debugging tools, which groups every command issued
+/// until it is dropped. Groups can be nested.
+#[derive(Debug)]
+pub struct DebugGroup {
+    _pushed: bool,
+}
+
+impl DebugGroup {
+    /// Push a new debug group with the given name. Does nothing if KHR_debug
+    /// is not supported.
+    pub fn push(name: &str) -> Self {
+        let pushed = gl::PushDebugGroup::is_loaded();
+        if pushed {
+            unsafe {
+                gl_call!(PushDebugGroup(
+                    gl::DEBUG_SOURCE_APPLICATION,
+                    0,
+                    name.len() as GLsizei,
+                    name.as_ptr() as *const GLchar
+                ));
+            }
+        }
+        return DebugGroup { _pushed: pushed };
+    }
+
+    /// Pop the debug group.
+    pub fn pop(self) {}
+}
+
+impl Drop for DebugGroup {
+    fn drop(&mut self) {
+        if self._pushed {
+            unsafe {
+                gl_call!(PopDebugGroup());
+            }
+        }
+    }
+}
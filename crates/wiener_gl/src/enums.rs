@@ -0,0 +1,243 @@
+use bitflags::bitflags;
+use gl;
+use gl::types::*;
+
+/// Define an enum whose variants are OpenGL constants, along with the
+/// conversion into `GLenum`.
+macro_rules! gl_enum {
+    ($(#[$meta:meta])* $name:ident { $($(#[$vmeta:meta])* $variant:ident = $value:path),* $(,)? }) => {
+        $(#[$meta])*
+        #[repr(u32)]
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $($(#[$vmeta])* $variant = $value,)*
+        }
+
+        impl From<$name> for GLenum {
+            fn from(value: $name) -> Self {
+                return value as GLenum;
+            }
+        }
+    };
+}
+
+gl_enum!(
+    /// Capability that can be enabled or disabled with `GLManager`.
+    Capability {
+        Blend = gl::BLEND,
+        CullFace = gl::CULL_FACE,
+        DepthClamp = gl::DEPTH_CLAMP,
+        DepthTest = gl::DEPTH_TEST,
+        Dither = gl::DITHER,
+        FramebufferSrgb = gl::FRAMEBUFFER_SRGB,
+        LineSmooth = gl::LINE_SMOOTH,
+        Multisample = gl::MULTISAMPLE,
+        PolygonOffsetFill = gl::POLYGON_OFFSET_FILL,
+        PolygonOffsetLine = gl::POLYGON_OFFSET_LINE,
+        PolygonOffsetPoint = gl::POLYGON_OFFSET_POINT,
+        PrimitiveRestart = gl::PRIMITIVE_RESTART,
+        ProgramPointSize = gl::PROGRAM_POINT_SIZE,
+        RasterizerDiscard = gl::RASTERIZER_DISCARD,
+        SampleAlphaToCoverage = gl::SAMPLE_ALPHA_TO_COVERAGE,
+        SampleShading = gl::SAMPLE_SHADING,
+        ScissorTest = gl::SCISSOR_TEST,
+        StencilTest = gl::STENCIL_TEST,
+        TextureCubeMapSeamless = gl::TEXTURE_CUBE_MAP_SEAMLESS,
+        DebugOutput = gl::DEBUG_OUTPUT,
+        DebugOutputSynchronous = gl::DEBUG_OUTPUT_SYNCHRONOUS,
+    }
+);
+
+gl_enum!(
+    /// Factor of a blending function.
+    BlendFactor {
+        Zero = gl::ZERO,
+        One = gl::ONE,
+        SrcColor = gl::SRC_COLOR,
+        OneMinusSrcColor = gl::ONE_MINUS_SRC_COLOR,
+        DstColor = gl::DST_COLOR,
+        OneMinusDstColor = gl::ONE_MINUS_DST_COLOR,
+        SrcAlpha = gl::SRC_ALPHA,
+        OneMinusSrcAlpha = gl::ONE_MINUS_SRC_ALPHA,
+        DstAlpha = gl::DST_ALPHA,
+        OneMinusDstAlpha = gl::ONE_MINUS_DST_ALPHA,
+        ConstantColor = gl::CONSTANT_COLOR,
+        OneMinusConstantColor = gl::ONE_MINUS_CONSTANT_COLOR,
+        ConstantAlpha = gl::CONSTANT_ALPHA,
+        OneMinusConstantAlpha = gl::ONE_MINUS_CONSTANT_ALPHA,
+        SrcAlphaSaturate = gl::SRC_ALPHA_SATURATE,
+    }
+);
+
+gl_enum!(
+    /// Way polygons are rasterized.
+    PolygonMode {
+        Point = gl::POINT,
+        Line = gl::LINE,
+        Fill = gl::FILL,
+    }
+);
+
+gl_enum!(
+    /// Face of a polygon.
+    Face {
+        Front = gl::FRONT,
+        Back = gl::BACK,
+        FrontAndBack = gl::FRONT_AND_BACK,
+    }
+);
+
+gl_enum!(
+    /// Equation used to combine the source and destination colors when
+    /// blending.
+    BlendEquation {
+        Add = gl::FUNC_ADD,
+        Subtract = gl::FUNC_SUBTRACT,
+        ReverseSubtract = gl::FUNC_REVERSE_SUBTRACT,
+        Min = gl::MIN,
+        Max = gl::MAX,
+    }
+);
+
+gl_enum!(
+    /// Winding order of the front faces of polygons.
+    FrontFace {
+        Clockwise = gl::CW,
+        CounterClockwise = gl::CCW,
+    }
+);
+
+gl_enum!(
+    /// Function used to compare a value against a reference, as in depth and
+    /// stencil tests.
+    CompareFunc {
+        Never = gl::NEVER,
+        Less = gl::LESS,
+        Equal = gl::EQUAL,
+        LessEqual = gl::LEQUAL,
+        Greater = gl::GREATER,
+        NotEqual = gl::NOTEQUAL,
+        GreaterEqual = gl::GEQUAL,
+        Always = gl::ALWAYS,
+    }
+);
+
+gl_enum!(
+    /// Action taken on the stencil buffer after a stencil or depth test.
+    StencilOp {
+        Keep = gl::KEEP,
+        Zero = gl::ZERO,
+        Replace = gl::REPLACE,
+        Increment = gl::INCR,
+        IncrementWrap = gl::INCR_WRAP,
+        Decrement = gl::DECR,
+        DecrementWrap = gl::DECR_WRAP,
+        Invert = gl::INVERT,
+    }
+);
+
+gl_enum!(
+    /// Wrapping method of a texture.
+    TextureWrap {
+        Repeat = gl::REPEAT,
+        MirroredRepeat = gl::MIRRORED_REPEAT,
+        ClampToEdge = gl::CLAMP_TO_EDGE,
+        ClampToBorder = gl::CLAMP_TO_BORDER,
+    }
+);
+
+gl_enum!(
+    /// Filter used when a texture is minified.
+    MinFilter {
+        Nearest = gl::NEAREST,
+        Linear = gl::LINEAR,
+        NearestMipmapNearest = gl::NEAREST_MIPMAP_NEAREST,
+        LinearMipmapNearest = gl::LINEAR_MIPMAP_NEAREST,
+        NearestMipmapLinear = gl::NEAREST_MIPMAP_LINEAR,
+        LinearMipmapLinear = gl::LINEAR_MIPMAP_LINEAR,
+    }
+);
+
+gl_enum!(
+    /// Filter used when a texture is magnified. Mipmaps are never used for
+    /// magnification, so only nearest and linear filtering are available.
+    MagFilter {
+        Nearest = gl::NEAREST,
+        Linear = gl::LINEAR,
+    }
+);
+
+gl_enum!(
+    /// Data type of a vertex attribute.
+    DataType {
+        Byte = gl::BYTE,
+        UnsignedByte = gl::UNSIGNED_BYTE,
+        Short = gl::SHORT,
+        UnsignedShort = gl::UNSIGNED_SHORT,
+        Int = gl::INT,
+        UnsignedInt = gl::UNSIGNED_INT,
+        HalfFloat = gl::HALF_FLOAT,
+        Float = gl::FLOAT,
+        Double = gl::DOUBLE,
+    }
+);
+
+gl_enum!(
+    /// Kind of value measured by a `Query`.
+    QueryTarget {
+        TimeElapsed = gl::TIME_ELAPSED,
+        Timestamp = gl::TIMESTAMP,
+        SamplesPassed = gl::SAMPLES_PASSED,
+        AnySamplesPassed = gl::ANY_SAMPLES_PASSED,
+        PrimitivesGenerated = gl::PRIMITIVES_GENERATED,
+    }
+);
+
+gl_enum!(
+    /// Way conditional rendering waits for the result of its query.
+    ConditionalRenderMode {
+        Wait = gl::QUERY_WAIT,
+        NoWait = gl::QUERY_NO_WAIT,
+        ByRegionWait = gl::QUERY_BY_REGION_WAIT,
+        ByRegionNoWait = gl::QUERY_BY_REGION_NO_WAIT,
+    }
+);
+
+gl_enum!(
+    /// Kind of resource, as used by `glObjectLabel` and the registry of a
+    /// `GLContext`. Fences are labeled by pointer instead, so `Sync` is only
+    /// used by the registry.
+    ResourceKind {
+        Buffer = gl::BUFFER,
+        Texture = gl::TEXTURE,
+        RenderBuffer = gl::RENDERBUFFER,
+        FrameBuffer = gl::FRAMEBUFFER,
+        VertexArray = gl::VERTEX_ARRAY,
+        Shader = gl::SHADER,
+        Program = gl::PROGRAM,
+        Query = gl::QUERY,
+        Sync = gl::SYNC_FENCE,
+    }
+);
+
+impl ResourceKind {
+    /// Get whether objects of this kind are shared between the contexts of a
+    /// group or not. Objects that contain other objects (framebuffers,
+    /// vertex arrays and queries) belong to a single context.
+    pub fn is_shared(&self) -> bool {
+        return !matches!(
+            self,
+            ResourceKind::FrameBuffer | ResourceKind::VertexArray | ResourceKind::Query
+        );
+    }
+}
+
+bitflags! {
+    /// Buffers to clear with `GLManager::clear`.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    pub struct ClearMask: GLbitfield {
+        const COLOR = gl::COLOR_BUFFER_BIT;
+        const DEPTH = gl::DEPTH_BUFFER_BIT;
+        const STENCIL = gl::STENCIL_BUFFER_BIT;
+    }
+}
@@ -0,0 +1,107 @@
+use crate::{GLContext, HasContext, ResourceKind};
+
+use gl;
+use gl::types::*;
+use log;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Duration;
+
+/// Last number given to a fence, which identifies it in the registry.
+static LAST_ID: AtomicU32 = AtomicU32::new(0);
+
+/// Fence sync object, signaled once every command issued before it has been
+/// completed by the GPU.
+#[derive(Debug)]
+pub struct Fence {
+    _id: u32,
+    _sync: GLsync,
+    _context: GLContext,
+}
+
+impl HasContext for Fence {
+    fn get_context(&self) -> &GLContext {
+        return &self._context;
+    }
+}
+
+impl Fence {
+    /// Insert a new fence after the commands issued so far in the given
+    /// context.
+    #[track_caller]
+    pub fn new(context: &GLContext) -> Self {
+        context.debug_assert_current("Fence");
+        let id = LAST_ID.fetch_add(1, Ordering::Relaxed) + 1;
+        log::trace!("Fence :: Inserting fence {:?}", id);
+        let sync = unsafe { gl_call!(FenceSync(gl::SYNC_GPU_COMMANDS_COMPLETE, 0)) };
+        context.register_resource(ResourceKind::Sync, id);
+        return Fence {
+            _id: id,
+            _sync: sync,
+            _context: context.clone(),
+        };
+    }
+
+    /// Get whether the fence has been signaled or not, without blocking.
+    pub fn is_signaled(&self) -> bool {
+        self._context.debug_assert_current("Fence");
+        let mut status = 0;
+        unsafe {
+            gl_call!(GetSynciv(
+                self._sync,
+                gl::SYNC_STATUS,
+                1,
+                std::ptr::null_mut(),
+                &mut status
+            ));
+        }
+        return status as GLenum == gl::SIGNALED;
+    }
+
+    /// Block until the fence is signaled or the timeout expires, returning
+    /// whether it was signaled or not. Pending commands are flushed first, so
+    /// the fence is guaranteed to be signaled eventually.
+    pub fn wait(&self, timeout: Duration) -> bool {
+        self._context.debug_assert_current("Fence");
+        log::trace!("Fence :: Waiting up to {:?}", timeout);
+        let timeout = timeout.as_nanos().min(u64::MAX as u128) as u64;
+        let result = unsafe {
+            gl_call!(ClientWaitSync(
+                self._sync,
+                gl::SYNC_FLUSH_COMMANDS_BIT,
+                timeout
+            ))
+        };
+        return match result {
+            gl::ALREADY_SIGNALED | gl::CONDITION_SATISFIED => true,
+            gl::TIMEOUT_EXPIRED => false,
+            _ => {
+                log::error!("Fence :: Waiting on the fence failed");
+                false
+            }
+        };
+    }
+
+    /// Make the GPU wait for the fence before executing the following
+    /// commands, without blocking the CPU.
+    pub fn gpu_wait(&self) {
+        self._context.debug_assert_current("Fence");
+        log::trace!("Fence :: Making the GPU wait");
+        unsafe {
+            gl_call!(WaitSync(self._sync, 0, gl::TIMEOUT_IGNORED));
+        }
+    }
+}
+
+impl Drop for Fence {
+    fn drop(&mut self) {
+        if !self._context.check_delete("Fence") {
+            return;
+        }
+        log::trace!("Fence :: Deleting fence {:?}", self._id);
+        unsafe {
+            gl_call!(DeleteSync(self._sync));
+        }
+        self._context
+            .unregister_resource(ResourceKind::Sync, self._id);
+    }
+}
@@ -1,12 +1,16 @@
-use crate::{Bindable, HasID, RenderBuffer, Texture, Texture2D};
+use crate::{
+    Bindable, GLContext, HasContext, HasID, Labelable, RenderBuffer, ResourceKind, StateCache,
+    Texture, Texture2D,
+};
 
 use gl;
 use gl::types::*;
 
 /// OpenGL framebuffer.
-#[derive(Clone, Debug)]
+#[derive(Debug)]
 pub struct FrameBuffer {
     _id: u32,
+    _context: GLContext,
 }
 
 impl HasID for FrameBuffer {
@@ -15,21 +19,40 @@ impl HasID for FrameBuffer {
     }
 }
 
+impl HasContext for FrameBuffer {
+    fn get_context(&self) -> &GLContext {
+        return &self._context;
+    }
+}
+
+impl Labelable for FrameBuffer {
+    fn get_resource_kind(&self) -> ResourceKind {
+        return ResourceKind::FrameBuffer;
+    }
+}
+
 impl FrameBuffer {
-    /// Create a new framebuffer.
-    pub fn new() -> Self {
+    /// Create a new framebuffer in the given context. Framebuffers are not
+    /// shared between contexts.
+    #[track_caller]
+    pub fn new(context: &GLContext) -> Self {
+        context.debug_assert_exactly_current("FrameBuffer");
         let mut fbo_id = 0;
         unsafe {
-            gl::GenFramebuffers(1, &mut fbo_id);
+            gl_call!(GenFramebuffers(1, &mut fbo_id));
         }
-        return FrameBuffer { _id: fbo_id };
+        context.register_resource(ResourceKind::FrameBuffer, fbo_id);
+        return FrameBuffer {
+            _id: fbo_id,
+            _context: context.clone(),
+        };
     }
 
     /// Verify the validity of the framebuffer.
     pub fn verify(&self) {
         self.bind();
         unsafe {
-            if gl::CheckFramebufferStatus(gl::FRAMEBUFFER) != gl::FRAMEBUFFER_COMPLETE {
+            if gl_call!(CheckFramebufferStatus(gl::FRAMEBUFFER)) != gl::FRAMEBUFFER_COMPLETE {
                 panic!("The framebuffer is not complete");
             }
         }
@@ -40,12 +63,12 @@ impl FrameBuffer {
     pub fn inplace_attach_renderbuffer(&self, attachment: GLenum, target: &RenderBuffer) {
         self.bind();
         unsafe {
-            gl::FramebufferRenderbuffer(
+            gl_call!(FramebufferRenderbuffer(
                 gl::FRAMEBUFFER,
                 attachment,
                 gl::RENDERBUFFER,
                 target.get_id(),
-            );
+            ));
         }
         self.unbind();
     }
@@ -54,7 +77,12 @@ impl FrameBuffer {
     pub fn inplace_attach_texture(&self, attachment: GLenum, target: &dyn Texture) {
         self.bind();
         unsafe {
-            gl::FramebufferTexture(gl::FRAMEBUFFER, attachment, target.get_id(), 0);
+            gl_call!(FramebufferTexture(
+                gl::FRAMEBUFFER,
+                attachment,
+                target.get_id(),
+                0
+            ));
         }
         self.unbind();
     }
@@ -78,7 +106,13 @@ impl FrameBuffer {
     ) {
         self.bind();
         unsafe {
-            gl::FramebufferTexture2D(gl::FRAMEBUFFER, attachment, target_type, target.get_id(), 0);
+            gl_call!(FramebufferTexture2D(
+                gl::FRAMEBUFFER,
+                attachment,
+                target_type,
+                target.get_id(),
+                0
+            ));
         }
         self.unbind();
     }
@@ -151,17 +185,13 @@ impl FrameBuffer {
     /// Bind to the read framebuffer.
     pub fn bind_read(&self) {
         log::trace!("FrameBuffer :: Binding to the read framebuffer");
-        unsafe {
-            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, self.get_id());
-        }
+        StateCache::bind_framebuffer(gl::READ_FRAMEBUFFER, self.get_id());
     }
 
     /// Bind to the draw framebuffer.
     pub fn bind_draw(&self) {
         log::trace!("FrameBuffer :: Binding to the draw framebuffer");
-        unsafe {
-            gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, self.get_id());
-        }
+        StateCache::bind_framebuffer(gl::DRAW_FRAMEBUFFER, self.get_id());
     }
 
     /// Blit the framebuffer's texture to another framebuffer.
@@ -177,7 +207,7 @@ impl FrameBuffer {
         self.bind_read();
         target.bind_draw();
         unsafe {
-            gl::BlitFramebuffer(
+            gl_call!(BlitFramebuffer(
                 source_coords.0,
                 source_coords.1,
                 source_coords.2,
@@ -188,7 +218,7 @@ impl FrameBuffer {
                 target_coords.3,
                 mask,
                 filter,
-            );
+            ));
         }
         self.unbind();
         target.unbind();
@@ -197,29 +227,29 @@ impl FrameBuffer {
 
 impl Bindable for FrameBuffer {
     fn bind(&self) {
+        self._context.debug_assert_exactly_current("FrameBuffer");
         log::trace!("FrameBuffer :: Binding");
-        unsafe {
-            gl::BindFramebuffer(gl::FRAMEBUFFER, self.get_id());
-        }
+        StateCache::bind_framebuffer(gl::FRAMEBUFFER, self.get_id());
     }
 
     fn unbind(&self) {
+        self._context.debug_assert_exactly_current("FrameBuffer");
         log::trace!("FrameBuffer :: Unbinding");
-        unsafe {
-            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
-        }
-    }
-
-    fn delete(&self) {
-        log::trace!("FrameBuffer :: Deleting");
-        unsafe {
-            gl::DeleteFramebuffers(1, &self.get_id());
-        }
+        StateCache::bind_framebuffer(gl::FRAMEBUFFER, 0);
     }
 }
 
 impl Drop for FrameBuffer {
     fn drop(&mut self) {
-        self.delete();
+        if !self._context.check_delete_exactly("FrameBuffer") {
+            return;
+        }
+        log::trace!("FrameBuffer :: Deleting");
+        StateCache::forget_framebuffer(self.get_id());
+        unsafe {
+            gl_call!(DeleteFramebuffers(1, &self.get_id()));
+        }
+        self._context
+            .unregister_resource(ResourceKind::FrameBuffer, self.get_id());
     }
 }
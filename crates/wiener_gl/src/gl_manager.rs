@@ -1,3 +1,8 @@
+use crate::{
+    BlendEquation, BlendFactor, Capability, ClearMask, CompareFunc, Face, FrontFace, PolygonMode,
+    StateCache, StencilOp,
+};
+
 use gl;
 use gl::types::*;
 use log;
@@ -8,39 +13,42 @@ use log;
 pub struct GLManager;
 
 impl GLManager {
-    pub fn enable(feature: GLenum) {
+    pub fn enable(feature: Capability) {
         log::trace!("GLManager :: Enabling feature {:?}", feature);
         unsafe {
-            gl::Enable(feature);
+            gl_call!(Enable(feature as GLenum));
         }
+        StateCache::invalidate_render_state();
     }
 
-    pub fn disable(feature: GLenum) {
+    pub fn disable(feature: Capability) {
         log::trace!("GLManager :: Disabling feature {:?}", feature);
         unsafe {
-            gl::Disable(feature);
+            gl_call!(Disable(feature as GLenum));
         }
+        StateCache::invalidate_render_state();
     }
 
     pub fn clear_color(red: f32, green: f32, blue: f32, alpha: f32) {
         log::trace!("GLManager :: Setting clear color");
         unsafe {
-            gl::ClearColor(red, green, blue, alpha);
+            gl_call!(ClearColor(red, green, blue, alpha));
         }
     }
 
-    pub fn clear(mask: u32) {
+    pub fn clear(mask: ClearMask) {
         log::trace!("GLManager :: Clearing screen");
         unsafe {
-            gl::Clear(mask);
+            gl_call!(Clear(mask.bits()));
         }
     }
 
-    pub fn blend_func(sfactor: u32, dfactor: u32) {
+    pub fn blend_func(sfactor: BlendFactor, dfactor: BlendFactor) {
         log::trace!("GLManager :: Setting blending function");
         unsafe {
-            gl::BlendFunc(sfactor, dfactor);
+            gl_call!(BlendFunc(sfactor as GLenum, dfactor as GLenum));
         }
+        StateCache::invalidate_render_state();
     }
 
     pub fn viewport(start_x: i32, start_y: i32, width: i32, height: i32) {
@@ -52,18 +60,212 @@ impl GLManager {
             start_y
         );
         unsafe {
-            gl::Viewport(start_x, start_y, width, height);
+            gl_call!(Viewport(start_x, start_y, width, height));
         }
     }
 
-    pub fn polygon_mode(face: GLenum, mode: GLenum) {
+    pub fn polygon_mode(face: Face, mode: PolygonMode) {
         log::trace!(
             "GLManager :: Setting polygon mode on face {:?} to {:?}",
             face,
             mode
         );
         unsafe {
-            gl::PolygonMode(face, mode);
+            gl_call!(PolygonMode(face as GLenum, mode as GLenum));
+        }
+        StateCache::invalidate_render_state();
+    }
+
+    /// Set the function used by the depth test.
+    pub fn depth_func(func: CompareFunc) {
+        log::trace!("GLManager :: Setting depth function to {:?}", func);
+        unsafe {
+            gl_call!(DepthFunc(func as GLenum));
+        }
+        StateCache::invalidate_render_state();
+    }
+
+    /// Enable or disable writing to the depth buffer.
+    pub fn depth_mask(enabled: bool) {
+        log::trace!("GLManager :: Setting depth mask to {:?}", enabled);
+        unsafe {
+            gl_call!(DepthMask(enabled as GLboolean));
+        }
+        StateCache::invalidate_render_state();
+    }
+
+    /// Set the value the depth buffer is cleared to.
+    pub fn clear_depth(depth: f64) {
+        log::trace!("GLManager :: Setting clear depth to {:?}", depth);
+        unsafe {
+            gl_call!(ClearDepth(depth));
+        }
+    }
+
+    /// Set the value the stencil buffer is cleared to.
+    pub fn clear_stencil(value: i32) {
+        log::trace!("GLManager :: Setting clear stencil to {:?}", value);
+        unsafe {
+            gl_call!(ClearStencil(value));
+        }
+    }
+
+    /// Set the function, reference value and mask of the stencil test.
+    pub fn stencil_func(func: CompareFunc, reference: i32, mask: u32) {
+        log::trace!(
+            "GLManager :: Setting stencil function to {:?} with reference {:?} and mask {:?}",
+            func,
+            reference,
+            mask
+        );
+        unsafe {
+            gl_call!(StencilFunc(func as GLenum, reference, mask));
+        }
+        StateCache::invalidate_render_state();
+    }
+
+    /// Set the actions taken on the stencil buffer after the stencil and
+    /// depth tests.
+    pub fn stencil_op(stencil_fail: StencilOp, depth_fail: StencilOp, pass: StencilOp) {
+        log::trace!(
+            "GLManager :: Setting stencil operations to {:?}, {:?} and {:?}",
+            stencil_fail,
+            depth_fail,
+            pass
+        );
+        unsafe {
+            gl_call!(StencilOp(
+                stencil_fail as GLenum,
+                depth_fail as GLenum,
+                pass as GLenum
+            ));
+        }
+        StateCache::invalidate_render_state();
+    }
+
+    /// Set the mask of the bits that can be written to the stencil buffer.
+    pub fn stencil_mask(mask: u32) {
+        log::trace!("GLManager :: Setting stencil mask to {:?}", mask);
+        unsafe {
+            gl_call!(StencilMask(mask));
+        }
+        StateCache::invalidate_render_state();
+    }
+
+    /// Set the face culled when face culling is enabled.
+    pub fn cull_face(face: Face) {
+        log::trace!("GLManager :: Setting culled face to {:?}", face);
+        unsafe {
+            gl_call!(CullFace(face as GLenum));
+        }
+        StateCache::invalidate_render_state();
+    }
+
+    /// Set the winding order of the front faces.
+    pub fn front_face(front: FrontFace) {
+        log::trace!("GLManager :: Setting front face to {:?}", front);
+        unsafe {
+            gl_call!(FrontFace(front as GLenum));
+        }
+        StateCache::invalidate_render_state();
+    }
+
+    /// Set the scissor box, used when the scissor test is enabled.
+    pub fn scissor(start_x: i32, start_y: i32, width: i32, height: i32) {
+        log::trace!(
+            "GLManager :: Setting scissor box to {:?}x{:?} at ({:?}, {:?})",
+            width,
+            height,
+            start_x,
+            start_y
+        );
+        unsafe {
+            gl_call!(Scissor(start_x, start_y, width, height));
+        }
+        StateCache::invalidate_render_state();
+    }
+
+    /// Set which color channels are written.
+    pub fn color_mask(red: bool, green: bool, blue: bool, alpha: bool) {
+        log::trace!("GLManager :: Setting color mask");
+        unsafe {
+            gl_call!(ColorMask(
+                red as GLboolean,
+                green as GLboolean,
+                blue as GLboolean,
+                alpha as GLboolean
+            ));
+        }
+        StateCache::invalidate_render_state();
+    }
+
+    /// Set the blending equation of both the color and alpha channels.
+    pub fn blend_equation(equation: BlendEquation) {
+        log::trace!("GLManager :: Setting blending equation to {:?}", equation);
+        unsafe {
+            gl_call!(BlendEquation(equation as GLenum));
+        }
+        StateCache::invalidate_render_state();
+    }
+
+    /// Set separate blending equations for the color and alpha channels.
+    pub fn blend_equation_separate(rgb: BlendEquation, alpha: BlendEquation) {
+        log::trace!("GLManager :: Setting separate blending equations");
+        unsafe {
+            gl_call!(BlendEquationSeparate(rgb as GLenum, alpha as GLenum));
+        }
+        StateCache::invalidate_render_state();
+    }
+
+    /// Set separate blending functions for the color and alpha channels.
+    pub fn blend_func_separate(
+        src_rgb: BlendFactor,
+        dst_rgb: BlendFactor,
+        src_alpha: BlendFactor,
+        dst_alpha: BlendFactor,
+    ) {
+        log::trace!("GLManager :: Setting separate blending functions");
+        unsafe {
+            gl_call!(BlendFuncSeparate(
+                src_rgb as GLenum,
+                dst_rgb as GLenum,
+                src_alpha as GLenum,
+                dst_alpha as GLenum
+            ));
+        }
+        StateCache::invalidate_render_state();
+    }
+
+    /// Set the scale and units of the polygon offset, used when a polygon
+    /// offset capability is enabled.
+    pub fn polygon_offset(factor: f32, units: f32) {
+        log::trace!(
+            "GLManager :: Setting polygon offset to factor {:?} and units {:?}",
+            factor,
+            units
+        );
+        unsafe {
+            gl_call!(PolygonOffset(factor, units));
+        }
+        StateCache::invalidate_render_state();
+    }
+
+    /// Set the width of rasterized lines.
+    pub fn line_width(width: f32) {
+        log::trace!("GLManager :: Setting line width to {:?}", width);
+        unsafe {
+            gl_call!(LineWidth(width));
+        }
+        StateCache::invalidate_render_state();
+    }
+
+    /// Set the size of rasterized points, used unless the program point size
+    /// capability is enabled.
+    pub fn point_size(size: f32) {
+        log::trace!("GLManager :: Setting point size to {:?}", size);
+        unsafe {
+            gl_call!(PointSize(size));
         }
+        StateCache::invalidate_render_state();
     }
 }
@@ -1,21 +1,47 @@
 //! Crate that contains the code to connect Wiener with OpenGL
 
+#[macro_use]
+mod debug;
+
+mod app;
 mod buffers;
+mod capabilities;
+mod context;
+mod enums;
+mod fence;
 mod file_handlers;
 mod framebuffer;
 mod gl_manager;
+#[cfg(feature = "gl-mock")]
+mod mock;
+mod query;
+mod registry;
+mod render_state;
 mod shader;
 mod shapes;
+mod state_cache;
 mod textures;
 mod types;
 mod window;
 
+pub use app::*;
 pub use buffers::*;
+pub use capabilities::*;
+pub use context::*;
+pub use debug::*;
+pub use enums::*;
+pub use fence::*;
 pub use file_handlers::*;
 pub use framebuffer::*;
 pub use gl_manager::*;
+#[cfg(feature = "gl-mock")]
+pub use mock::*;
+pub use query::*;
+pub use registry::*;
+pub use render_state::*;
 pub use shader::*;
 pub use shapes::*;
+pub use state_cache::*;
 pub use textures::*;
 pub use types::*;
 pub use window::*;
@@ -0,0 +1,918 @@
+use crate::{Capability, GLContext, StateCache};
+
+use gl;
+use gl::types::*;
+use log;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::ffi::{c_void, CStr};
+use std::fmt;
+
+/// Kind of object simulated by `MockGL`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MockObject {
+    Buffer,
+    Texture,
+    VertexArray,
+    Framebuffer,
+    Renderbuffer,
+    Program,
+    Shader,
+    Query,
+    Sync,
+}
+
+/// OpenGL call recorded by `MockGL`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MockCall {
+    /// Name of the function, without the `gl` prefix.
+    pub name: &'static str,
+
+    /// Arguments of the call. Pointers are recorded as `ptr` or `null`, since
+    /// their addresses change between runs.
+    pub args: Vec<String>,
+}
+
+impl fmt::Display for MockCall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return write!(f, "{}({})", self.name, self.args.join(", "));
+    }
+}
+
+/// Argument of a mocked function, which can be recorded.
+trait MockArg {
+    fn describe(&self) -> String;
+}
+
+macro_rules! impl_mock_arg {
+    ($($ty:ty),*) => {
+        $(
+            impl MockArg for $ty {
+                fn describe(&self) -> String {
+                    return self.to_string();
+                }
+            }
+        )*
+    };
+}
+
+impl_mock_arg!(u8, i32, u32, i64, u64, isize, f32, f64);
+
+impl<T> MockArg for *const T {
+    fn describe(&self) -> String {
+        return if self.is_null() { "null" } else { "ptr" }.to_string();
+    }
+}
+
+impl<T> MockArg for *mut T {
+    fn describe(&self) -> String {
+        return if self.is_null() { "null" } else { "ptr" }.to_string();
+    }
+}
+
+impl MockArg for GLDEBUGPROC {
+    fn describe(&self) -> String {
+        return if self.is_some() { "ptr" } else { "null" }.to_string();
+    }
+}
+
+/// State of the simulated context.
+#[derive(Default)]
+struct MockState {
+    calls: Vec<MockCall>,
+    errors: Vec<GLenum>,
+    last_ids: HashMap<MockObject, u32>,
+    objects: HashSet<(MockObject, u32)>,
+    integers: HashMap<GLenum, i32>,
+    capabilities: HashSet<GLenum>,
+    program: u32,
+    vertex_array: u32,
+    buffers: HashMap<GLenum, u32>,
+
+    /// Element buffers, keyed by the vertex array they are bound to.
+    element_buffers: HashMap<u32, u32>,
+    active_texture: u32,
+    textures: HashMap<(u32, GLenum), u32>,
+    draw_framebuffer: u32,
+    read_framebuffer: u32,
+    renderbuffer: u32,
+    uniform_locations: HashMap<(u32, String), i32>,
+
+    /// Results of the queries, where `None` means the result is not
+    /// available yet. Queries missing here have 0 available.
+    query_results: HashMap<u32, Option<u64>>,
+}
+
+impl MockState {
+    fn new() -> Self {
+        return MockState {
+            integers: HashMap::from([
+                (gl::MAX_TEXTURE_SIZE, 16384),
+                (gl::MAX_SAMPLES, 8),
+                (gl::MAX_COMBINED_TEXTURE_IMAGE_UNITS, 32),
+                (gl::MAX_UNIFORM_BLOCK_SIZE, 65536),
+                (gl::MAX_VERTEX_ATTRIBS, 16),
+                (gl::MAX_DRAW_BUFFERS, 8),
+            ]),
+            ..Default::default()
+        };
+    }
+
+    fn create(&mut self, kind: MockObject) -> u32 {
+        let id = self.last_ids.entry(kind).or_insert(0);
+        *id += 1;
+        self.objects.insert((kind, *id));
+        return *id;
+    }
+
+    fn destroy(&mut self, kind: MockObject, id: u32) {
+        if id == 0 || !self.objects.remove(&(kind, id)) {
+            return;
+        }
+
+        // Deleting a bound object binds zero instead, except for programs,
+        // which stay in use until another one is used.
+        match kind {
+            MockObject::Buffer => {
+                self.buffers.retain(|_, b| *b != id);
+                if self.element_buffers.get(&self.vertex_array) == Some(&id) {
+                    self.element_buffers.remove(&self.vertex_array);
+                }
+            }
+            MockObject::Texture => self.textures.retain(|_, t| *t != id),
+            MockObject::VertexArray => {
+                if self.vertex_array == id {
+                    self.vertex_array = 0;
+                }
+                self.element_buffers.remove(&id);
+            }
+            MockObject::Framebuffer => {
+                if self.draw_framebuffer == id {
+                    self.draw_framebuffer = 0;
+                }
+                if self.read_framebuffer == id {
+                    self.read_framebuffer = 0;
+                }
+            }
+            MockObject::Renderbuffer if self.renderbuffer == id => self.renderbuffer = 0,
+            _ => {}
+        }
+    }
+
+    /// Raise `GL_INVALID_OPERATION` if a non-zero name is not an object.
+    fn check(&mut self, kind: MockObject, id: u32) -> bool {
+        if id != 0 && !self.objects.contains(&(kind, id)) {
+            log::warn!("MockGL :: {:?} {:?} does not exist", kind, id);
+            self.errors.push(gl::INVALID_OPERATION);
+            return false;
+        }
+        return true;
+    }
+
+    fn get_integer(&self, pname: GLenum) -> i32 {
+        let value = match pname {
+            gl::CURRENT_PROGRAM => self.program,
+            gl::VERTEX_ARRAY_BINDING => self.vertex_array,
+            gl::ELEMENT_ARRAY_BUFFER_BINDING => self.get_buffer(gl::ELEMENT_ARRAY_BUFFER),
+            gl::ARRAY_BUFFER_BINDING => self.get_buffer(gl::ARRAY_BUFFER),
+            gl::UNIFORM_BUFFER_BINDING => self.get_buffer(gl::UNIFORM_BUFFER),
+            gl::ACTIVE_TEXTURE => gl::TEXTURE0 + self.active_texture,
+            gl::TEXTURE_BINDING_2D => self.get_texture(self.active_texture, gl::TEXTURE_2D),
+            gl::DRAW_FRAMEBUFFER_BINDING => self.draw_framebuffer,
+            gl::READ_FRAMEBUFFER_BINDING => self.read_framebuffer,
+            gl::RENDERBUFFER_BINDING => self.renderbuffer,
+            _ => return self.integers.get(&pname).copied().unwrap_or(0),
+        };
+        return value as i32;
+    }
+
+    fn get_buffer(&self, target: GLenum) -> u32 {
+        if target == gl::ELEMENT_ARRAY_BUFFER {
+            return self
+                .element_buffers
+                .get(&self.vertex_array)
+                .copied()
+                .unwrap_or(0);
+        }
+        return self.buffers.get(&target).copied().unwrap_or(0);
+    }
+
+    fn get_texture(&self, unit: u32, target: GLenum) -> u32 {
+        return self.textures.get(&(unit, target)).copied().unwrap_or(0);
+    }
+}
+
+thread_local! {
+    static STATE: RefCell<MockState> = RefCell::new(MockState::new());
+}
+
+fn with_state<T>(f: impl FnOnce(&mut MockState) -> T) -> T {
+    return STATE.with(|s| f(&mut s.borrow_mut()));
+}
+
+fn record(name: &'static str, args: Vec<String>) {
+    log::trace!("MockGL :: Calling {}", name);
+    with_state(|s| s.calls.push(MockCall { name, args }));
+}
+
+fn gen_objects(kind: MockObject, n: GLsizei, ids: *mut GLuint) {
+    for i in 0..n.max(0) as usize {
+        let id = with_state(|s| s.create(kind));
+        unsafe {
+            *ids.add(i) = id;
+        }
+    }
+}
+
+fn delete_objects(kind: MockObject, n: GLsizei, ids: *const GLuint) {
+    for i in 0..n.max(0) as usize {
+        let id = unsafe { *ids.add(i) };
+        with_state(|s| s.destroy(kind, id));
+    }
+}
+
+fn write<T>(ptr: *mut T, value: T) {
+    if !ptr.is_null() {
+        unsafe {
+            *ptr = value;
+        }
+    }
+}
+
+/// Define the mocked functions, which record every call before running
+/// their body, along with the loader that hands them to `gl::load_with`.
+macro_rules! mock_functions {
+    ($(fn $name:ident($($arg:ident: $ty:ty),*) $(-> $ret:ty)? $body:block)*) => {
+        $(
+            #[allow(non_snake_case)]
+            extern "system" fn $name($($arg: $ty),*) $(-> $ret)? {
+                record(stringify!($name), vec![$($arg.describe()),*]);
+                $body
+            }
+        )*
+
+        fn get_proc_address(name: &str) -> *const c_void {
+            return match name.strip_prefix("gl") {
+                $(Some(stringify!($name)) => $name as *const c_void,)*
+                _ => std::ptr::null(),
+            };
+        }
+    };
+}
+
+mock_functions! {
+    // Objects
+    fn GenBuffers(n: GLsizei, buffers: *mut GLuint) {
+        gen_objects(MockObject::Buffer, n, buffers);
+    }
+    fn GenTextures(n: GLsizei, textures: *mut GLuint) {
+        gen_objects(MockObject::Texture, n, textures);
+    }
+    fn GenVertexArrays(n: GLsizei, arrays: *mut GLuint) {
+        gen_objects(MockObject::VertexArray, n, arrays);
+    }
+    fn GenFramebuffers(n: GLsizei, framebuffers: *mut GLuint) {
+        gen_objects(MockObject::Framebuffer, n, framebuffers);
+    }
+    fn GenRenderbuffers(n: GLsizei, renderbuffers: *mut GLuint) {
+        gen_objects(MockObject::Renderbuffer, n, renderbuffers);
+    }
+    fn GenQueries(n: GLsizei, ids: *mut GLuint) {
+        gen_objects(MockObject::Query, n, ids);
+    }
+    fn CreateProgram() -> GLuint {
+        return with_state(|s| s.create(MockObject::Program));
+    }
+    fn CreateShader(type_: GLenum) -> GLuint {
+        return with_state(|s| s.create(MockObject::Shader));
+    }
+    fn FenceSync(condition: GLenum, flags: GLbitfield) -> GLsync {
+        return with_state(|s| s.create(MockObject::Sync)) as usize as GLsync;
+    }
+    fn DeleteBuffers(n: GLsizei, buffers: *const GLuint) {
+        delete_objects(MockObject::Buffer, n, buffers);
+    }
+    fn DeleteTextures(n: GLsizei, textures: *const GLuint) {
+        delete_objects(MockObject::Texture, n, textures);
+    }
+    fn DeleteVertexArrays(n: GLsizei, arrays: *const GLuint) {
+        delete_objects(MockObject::VertexArray, n, arrays);
+    }
+    fn DeleteFramebuffers(n: GLsizei, framebuffers: *const GLuint) {
+        delete_objects(MockObject::Framebuffer, n, framebuffers);
+    }
+    fn DeleteRenderbuffers(n: GLsizei, renderbuffers: *const GLuint) {
+        delete_objects(MockObject::Renderbuffer, n, renderbuffers);
+    }
+    fn DeleteQueries(n: GLsizei, ids: *const GLuint) {
+        delete_objects(MockObject::Query, n, ids);
+    }
+    fn DeleteProgram(program: GLuint) {
+        with_state(|s| s.destroy(MockObject::Program, program));
+    }
+    fn DeleteShader(shader: GLuint) {
+        with_state(|s| s.destroy(MockObject::Shader, shader));
+    }
+    fn DeleteSync(sync: GLsync) {
+        with_state(|s| s.destroy(MockObject::Sync, sync as usize as u32));
+    }
+
+    // Bindings
+    fn UseProgram(program: GLuint) {
+        with_state(|s| {
+            if s.check(MockObject::Program, program) {
+                s.program = program;
+            }
+        });
+    }
+    fn BindVertexArray(array: GLuint) {
+        with_state(|s| {
+            if s.check(MockObject::VertexArray, array) {
+                s.vertex_array = array;
+            }
+        });
+    }
+    fn BindBuffer(target: GLenum, buffer: GLuint) {
+        with_state(|s| {
+            if !s.check(MockObject::Buffer, buffer) {
+                return;
+            }
+            if target == gl::ELEMENT_ARRAY_BUFFER {
+                let vao = s.vertex_array;
+                s.element_buffers.insert(vao, buffer);
+            } else {
+                s.buffers.insert(target, buffer);
+            }
+        });
+    }
+    fn BindBufferBase(target: GLenum, index: GLuint, buffer: GLuint) {
+        with_state(|s| {
+            if s.check(MockObject::Buffer, buffer) {
+                s.buffers.insert(target, buffer);
+            }
+        });
+    }
+    fn ActiveTexture(texture: GLenum) {
+        with_state(|s| s.active_texture = texture - gl::TEXTURE0);
+    }
+    fn BindTexture(target: GLenum, texture: GLuint) {
+        with_state(|s| {
+            if s.check(MockObject::Texture, texture) {
+                let unit = s.active_texture;
+                s.textures.insert((unit, target), texture);
+            }
+        });
+    }
+    fn BindFramebuffer(target: GLenum, framebuffer: GLuint) {
+        with_state(|s| {
+            if !s.check(MockObject::Framebuffer, framebuffer) {
+                return;
+            }
+            if target != gl::READ_FRAMEBUFFER {
+                s.draw_framebuffer = framebuffer;
+            }
+            if target != gl::DRAW_FRAMEBUFFER {
+                s.read_framebuffer = framebuffer;
+            }
+        });
+    }
+    fn BindRenderbuffer(target: GLenum, renderbuffer: GLuint) {
+        with_state(|s| {
+            if s.check(MockObject::Renderbuffer, renderbuffer) {
+                s.renderbuffer = renderbuffer;
+            }
+        });
+    }
+
+    // State
+    fn Enable(cap: GLenum) {
+        with_state(|s| s.capabilities.insert(cap));
+    }
+    fn Disable(cap: GLenum) {
+        with_state(|s| s.capabilities.remove(&cap));
+    }
+    fn ClearColor(red: GLfloat, green: GLfloat, blue: GLfloat, alpha: GLfloat) {}
+    fn ClearDepth(depth: GLdouble) {}
+    fn ClearStencil(s: GLint) {}
+    fn Clear(mask: GLbitfield) {}
+    fn Viewport(x: GLint, y: GLint, width: GLsizei, height: GLsizei) {}
+    fn Scissor(x: GLint, y: GLint, width: GLsizei, height: GLsizei) {}
+    fn BlendFunc(sfactor: GLenum, dfactor: GLenum) {}
+    fn BlendFuncSeparate(
+        sfactorRGB: GLenum,
+        dfactorRGB: GLenum,
+        sfactorAlpha: GLenum,
+        dfactorAlpha: GLenum
+    ) {}
+    fn BlendEquation(mode: GLenum) {}
+    fn BlendEquationSeparate(modeRGB: GLenum, modeAlpha: GLenum) {}
+    fn DepthFunc(func: GLenum) {}
+    fn DepthMask(flag: GLboolean) {}
+    fn StencilFunc(func: GLenum, ref_: GLint, mask: GLuint) {}
+    fn StencilOp(fail: GLenum, zfail: GLenum, zpass: GLenum) {}
+    fn StencilMask(mask: GLuint) {}
+    fn ColorMask(red: GLboolean, green: GLboolean, blue: GLboolean, alpha: GLboolean) {}
+    fn CullFace(mode: GLenum) {}
+    fn FrontFace(mode: GLenum) {}
+    fn PolygonMode(face: GLenum, mode: GLenum) {}
+    fn PolygonOffset(factor: GLfloat, units: GLfloat) {}
+    fn LineWidth(width: GLfloat) {}
+    fn PointSize(size: GLfloat) {}
+
+    // Buffers and vertex arrays
+    fn BufferData(target: GLenum, size: GLsizeiptr, data: *const c_void, usage: GLenum) {}
+    fn BufferSubData(target: GLenum, offset: GLintptr, size: GLsizeiptr, data: *const c_void) {}
+    fn CopyBufferSubData(
+        read_target: GLenum,
+        write_target: GLenum,
+        read_offset: GLintptr,
+        write_offset: GLintptr,
+        size: GLsizeiptr
+    ) {}
+    fn VertexAttribPointer(
+        index: GLuint,
+        size: GLint,
+        type_: GLenum,
+        normalized: GLboolean,
+        stride: GLsizei,
+        pointer: *const c_void
+    ) {}
+    fn EnableVertexArrayAttrib(vaobj: GLuint, index: GLuint) {
+        with_state(|s| s.check(MockObject::VertexArray, vaobj));
+    }
+    fn DrawElements(mode: GLenum, count: GLsizei, type_: GLenum, indices: *const c_void) {}
+
+    // Textures, framebuffers and renderbuffers
+    fn TexImage2D(
+        target: GLenum,
+        level: GLint,
+        internalformat: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        border: GLint,
+        format: GLenum,
+        type_: GLenum,
+        pixels: *const c_void
+    ) {}
+    fn TexImage2DMultisample(
+        target: GLenum,
+        samples: GLsizei,
+        internalformat: GLenum,
+        width: GLsizei,
+        height: GLsizei,
+        fixedsamplelocations: GLboolean
+    ) {}
+    fn TexParameteri(target: GLenum, pname: GLenum, param: GLint) {}
+    fn GenerateMipmap(target: GLenum) {}
+    fn ReadPixels(
+        x: GLint,
+        y: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        format: GLenum,
+        type_: GLenum,
+        pixels: *mut c_void
+    ) {}
+    fn FramebufferTexture(target: GLenum, attachment: GLenum, texture: GLuint, level: GLint) {}
+    fn FramebufferTexture2D(
+        target: GLenum,
+        attachment: GLenum,
+        textarget: GLenum,
+        texture: GLuint,
+        level: GLint
+    ) {}
+    fn FramebufferRenderbuffer(
+        target: GLenum,
+        attachment: GLenum,
+        renderbuffertarget: GLenum,
+        renderbuffer: GLuint
+    ) {}
+    fn CheckFramebufferStatus(target: GLenum) -> GLenum {
+        return gl::FRAMEBUFFER_COMPLETE;
+    }
+    fn BlitFramebuffer(
+        srcX0: GLint,
+        srcY0: GLint,
+        srcX1: GLint,
+        srcY1: GLint,
+        dstX0: GLint,
+        dstY0: GLint,
+        dstX1: GLint,
+        dstY1: GLint,
+        mask: GLbitfield,
+        filter: GLenum
+    ) {}
+    fn RenderbufferStorage(
+        target: GLenum,
+        internalformat: GLenum,
+        width: GLsizei,
+        height: GLsizei
+    ) {}
+    fn RenderbufferStorageMultisample(
+        target: GLenum,
+        samples: GLsizei,
+        internalformat: GLenum,
+        width: GLsizei,
+        height: GLsizei
+    ) {}
+
+    // Shaders and uniforms
+    fn ShaderSource(
+        shader: GLuint,
+        count: GLsizei,
+        string: *const *const GLchar,
+        length: *const GLint
+    ) {}
+    fn CompileShader(shader: GLuint) {}
+    fn GetShaderiv(shader: GLuint, pname: GLenum, params: *mut GLint) {
+        write(params, (pname == gl::COMPILE_STATUS) as GLint);
+    }
+    fn GetShaderInfoLog(
+        shader: GLuint,
+        bufSize: GLsizei,
+        length: *mut GLsizei,
+        infoLog: *mut GLchar
+    ) {
+        write(length, 0);
+    }
+    fn AttachShader(program: GLuint, shader: GLuint) {}
+    fn LinkProgram(program: GLuint) {}
+    fn GetProgramiv(program: GLuint, pname: GLenum, params: *mut GLint) {
+        write(params, (pname == gl::LINK_STATUS) as GLint);
+    }
+    fn GetProgramInfoLog(
+        program: GLuint,
+        bufSize: GLsizei,
+        length: *mut GLsizei,
+        infoLog: *mut GLchar
+    ) {
+        write(length, 0);
+    }
+    fn GetUniformLocation(program: GLuint, name: *const GLchar) -> GLint {
+        let name = unsafe { CStr::from_ptr(name) }.to_string_lossy().into_owned();
+        return with_state(|s| {
+            let next = s.uniform_locations.len() as GLint;
+            return *s.uniform_locations.entry((program, name)).or_insert(next);
+        });
+    }
+    fn Uniform1i(location: GLint, v0: GLint) {}
+    fn Uniform2i(location: GLint, v0: GLint, v1: GLint) {}
+    fn Uniform3i(location: GLint, v0: GLint, v1: GLint, v2: GLint) {}
+    fn Uniform4i(location: GLint, v0: GLint, v1: GLint, v2: GLint, v3: GLint) {}
+    fn Uniform1f(location: GLint, v0: GLfloat) {}
+    fn Uniform2f(location: GLint, v0: GLfloat, v1: GLfloat) {}
+    fn Uniform3f(location: GLint, v0: GLfloat, v1: GLfloat, v2: GLfloat) {}
+    fn Uniform4f(location: GLint, v0: GLfloat, v1: GLfloat, v2: GLfloat, v3: GLfloat) {}
+    fn UniformMatrix2fv(
+        location: GLint,
+        count: GLsizei,
+        transpose: GLboolean,
+        value: *const GLfloat
+    ) {}
+    fn UniformMatrix3fv(
+        location: GLint,
+        count: GLsizei,
+        transpose: GLboolean,
+        value: *const GLfloat
+    ) {}
+    fn UniformMatrix4fv(
+        location: GLint,
+        count: GLsizei,
+        transpose: GLboolean,
+        value: *const GLfloat
+    ) {}
+
+    // Queries and syncs
+    fn BeginQuery(target: GLenum, id: GLuint) {
+        with_state(|s| s.check(MockObject::Query, id));
+    }
+    fn EndQuery(target: GLenum) {}
+    fn QueryCounter(id: GLuint, target: GLenum) {
+        with_state(|s| s.check(MockObject::Query, id));
+    }
+    fn GetQueryObjectiv(id: GLuint, pname: GLenum, params: *mut GLint) {
+        let available = with_state(|s| s.query_results.get(&id) != Some(&None));
+        write(
+            params,
+            (pname == gl::QUERY_RESULT_AVAILABLE && available) as GLint,
+        );
+    }
+    fn GetQueryObjectui64v(id: GLuint, pname: GLenum, params: *mut GLuint64) {
+        let result = with_state(|s| s.query_results.get(&id).copied().flatten());
+        write(params, result.unwrap_or(0));
+    }
+    fn BeginConditionalRender(id: GLuint, mode: GLenum) {
+        with_state(|s| s.check(MockObject::Query, id));
+    }
+    fn EndConditionalRender() {}
+    fn GetSynciv(
+        sync: GLsync,
+        pname: GLenum,
+        bufSize: GLsizei,
+        length: *mut GLsizei,
+        values: *mut GLint
+    ) {
+        write(values, gl::SIGNALED as GLint);
+    }
+    fn ClientWaitSync(sync: GLsync, flags: GLbitfield, timeout: GLuint64) -> GLenum {
+        return gl::ALREADY_SIGNALED;
+    }
+    fn WaitSync(sync: GLsync, flags: GLbitfield, timeout: GLuint64) {}
+
+    // Introspection and debugging
+    fn GetError() -> GLenum {
+        return with_state(|s| {
+            if s.errors.is_empty() {
+                return gl::NO_ERROR;
+            }
+            return s.errors.remove(0);
+        });
+    }
+    fn GetIntegerv(pname: GLenum, data: *mut GLint) {
+        write(data, with_state(|s| s.get_integer(pname)));
+    }
+    fn GetString(name: GLenum) -> *const GLubyte {
+        let value: &'static [u8] = match name {
+            gl::VERSION => b"4.6 Mock\0",
+            gl::RENDERER => b"MockGL\0",
+            gl::VENDOR => b"Wiener\0",
+            _ => return std::ptr::null(),
+        };
+        return value.as_ptr();
+    }
+    fn GetStringi(name: GLenum, index: GLuint) -> *const GLubyte {
+        return std::ptr::null();
+    }
+    fn DebugMessageCallback(callback: GLDEBUGPROC, userParam: *const c_void) {}
+    fn DebugMessageControl(
+        source: GLenum,
+        type_: GLenum,
+        severity: GLenum,
+        count: GLsizei,
+        ids: *const GLuint,
+        enabled: GLboolean
+    ) {}
+    fn ObjectLabel(identifier: GLenum, name: GLuint, length: GLsizei, label: *const GLchar) {}
+    fn PushDebugGroup(source: GLenum, id: GLuint, length: GLsizei, message: *const GLchar) {}
+    fn PopDebugGroup() {}
+}
+
+/// Recording OpenGL backend that needs no GPU, meant for unit tests.
+///
+/// `MockGL::load` swaps the function pointers loaded into `gl` for mocked
+/// ones, which record every call and simulate object ids and bindings. The
+/// simulated state is kept per thread, so tests can run in parallel. Calling
+/// a function the mock does not implement panics.
+pub struct MockGL;
+
+impl MockGL {
+    /// Load the mocked functions and reset the state of this thread,
+    /// returning a context made current on this thread to create resources.
+    pub fn load() -> GLContext {
+        log::info!("MockGL :: Loading mocked functions");
+        gl::load_with(get_proc_address);
+        MockGL::reset();
+        let context = GLContext::new();
+        context.set_current();
+        return context;
+    }
+
+    /// Reset the simulated context of this thread, along with the
+    /// `StateCache`.
+    pub fn reset() {
+        with_state(|s| *s = MockState::new());
+        StateCache::invalidate();
+    }
+
+    /// Get the calls recorded so far.
+    pub fn get_calls() -> Vec<MockCall> {
+        return with_state(|s| s.calls.clone());
+    }
+
+    /// Take the calls recorded so far, clearing them.
+    pub fn take_calls() -> Vec<MockCall> {
+        return with_state(|s| std::mem::take(&mut s.calls));
+    }
+
+    /// Take the errors raised and not yet read with `glGetError`.
+    pub fn take_errors() -> Vec<GLenum> {
+        return with_state(|s| std::mem::take(&mut s.errors));
+    }
+
+    /// Set the value returned by `glGetIntegerv` for a limit or flag.
+    pub fn set_integer(pname: GLenum, value: i32) {
+        with_state(|s| s.integers.insert(pname, value));
+    }
+
+    /// Set the result of a query, or `None` to make it unavailable.
+    pub fn set_query_result(id: u32, result: Option<u64>) {
+        with_state(|s| s.query_results.insert(id, result));
+    }
+
+    /// Get whether a capability is enabled or not.
+    pub fn is_enabled(capability: Capability) -> bool {
+        return with_state(|s| s.capabilities.contains(&(capability as GLenum)));
+    }
+
+    /// Get whether an object exists or not.
+    pub fn is_alive(kind: MockObject, id: u32) -> bool {
+        return with_state(|s| s.objects.contains(&(kind, id)));
+    }
+
+    /// Get the number of objects that have been created and not deleted.
+    pub fn get_live_objects() -> usize {
+        return with_state(|s| s.objects.len());
+    }
+
+    /// Get the program in use.
+    pub fn get_program() -> u32 {
+        return with_state(|s| s.program);
+    }
+
+    /// Get the bound vertex array.
+    pub fn get_vertex_array() -> u32 {
+        return with_state(|s| s.vertex_array);
+    }
+
+    /// Get the buffer bound to a target. The element buffer is the one of the
+    /// bound vertex array.
+    pub fn get_buffer_binding(target: GLenum) -> u32 {
+        return with_state(|s| s.get_buffer(target));
+    }
+
+    /// Get the active texture unit.
+    pub fn get_active_texture() -> u32 {
+        return with_state(|s| s.active_texture);
+    }
+
+    /// Get the texture bound to a target of a texture unit.
+    pub fn get_texture_binding(unit: u32, target: GLenum) -> u32 {
+        return with_state(|s| s.get_texture(unit, target));
+    }
+
+    /// Get the framebuffer bound to the draw or read target.
+    pub fn get_framebuffer_binding(target: GLenum) -> u32 {
+        return with_state(|s| match target {
+            gl::READ_FRAMEBUFFER => s.read_framebuffer,
+            _ => s.draw_framebuffer,
+        });
+    }
+
+    /// Get the bound renderbuffer.
+    pub fn get_renderbuffer_binding() -> u32 {
+        return with_state(|s| s.renderbuffer);
+    }
+}
+
+/// Take the calls recorded so far, formatted as `Name(args)`. The error
+/// checks added by the `gl-error-check` feature are left out.
+#[cfg(test)]
+pub(crate) fn take_call_strings() -> Vec<String> {
+    return MockGL::take_calls()
+        .iter()
+        .filter(|c| c.name != "GetError")
+        .map(|c| c.to_string())
+        .collect();
+}
+
+#[cfg(all(test, feature = "gl-mock"))]
+mod tests {
+    use super::*;
+    use crate::{
+        Bindable, Buffer, DataType, Drawable, FrameBuffer, HasID, Mesh, ShaderProgram, VertexArray,
+        VertexAttribute, VertexBuffer,
+    };
+    use std::rc::Rc;
+
+    #[test]
+    fn mesh_draw_binds_everything_and_draws_the_indices() {
+        let context = MockGL::load();
+        let program = Rc::new(ShaderProgram::new(&context));
+        let layout = [VertexAttribute::new(0, 3, DataType::Float)];
+        let mesh = Mesh::<f32, u16>::new(program.clone())
+            .layout(&layout)
+            .vertices(&[0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0])
+            .indices(&[0, 1, 2]);
+        MockGL::take_calls();
+
+        mesh.draw();
+        let calls = take_call_strings();
+        assert_eq!(
+            calls.last().map(String::as_str),
+            Some("DrawElements(4, 3, 5123, null)")
+        );
+        assert!(calls.contains(&format!("UseProgram({})", program.get_id())));
+        assert_eq!(MockGL::get_program(), program.get_id());
+        assert_eq!(MockGL::get_vertex_array(), mesh.vao.get_id());
+        assert_eq!(
+            MockGL::get_buffer_binding(gl::ELEMENT_ARRAY_BUFFER),
+            mesh.ebo.get_id()
+        );
+        assert!(MockGL::take_errors().is_empty());
+    }
+
+    #[test]
+    fn framebuffer_blit_binds_read_and_draw_targets() {
+        let context = MockGL::load();
+        let source = FrameBuffer::new(&context);
+        let target = FrameBuffer::new(&context);
+        MockGL::take_calls();
+
+        source.blit(
+            &target,
+            (0, 0, 64, 64),
+            (0, 0, 32, 32),
+            gl::COLOR_BUFFER_BIT,
+            gl::LINEAR,
+        );
+        assert_eq!(
+            take_call_strings(),
+            vec![
+                format!("BindFramebuffer(36008, {})", source.get_id()),
+                format!("BindFramebuffer(36009, {})", target.get_id()),
+                "BlitFramebuffer(0, 0, 64, 64, 0, 0, 32, 32, 16384, 9729)".to_string(),
+                "BindFramebuffer(36160, 0)".to_string(),
+            ]
+        );
+        assert_eq!(MockGL::get_framebuffer_binding(gl::READ_FRAMEBUFFER), 0);
+        assert_eq!(MockGL::get_framebuffer_binding(gl::DRAW_FRAMEBUFFER), 0);
+    }
+
+    #[test]
+    fn vertex_array_layout_sets_every_attribute() {
+        let context = MockGL::load();
+        let layout = [
+            VertexAttribute::new(0, 3, DataType::Float),
+            VertexAttribute::new(1, 2, DataType::Float),
+        ];
+        let vao = VertexArray::new(&context).size(4);
+        MockGL::take_calls();
+
+        let vao = vao.layout(&layout);
+        let id = vao.get_id();
+        assert_eq!(vao.stride, 5);
+        assert_eq!(
+            take_call_strings(),
+            vec![
+                format!("BindVertexArray({id})"),
+                "VertexAttribPointer(0, 3, 5126, 0, 20, null)".to_string(),
+                format!("EnableVertexArrayAttrib({id}, 0)"),
+                "VertexAttribPointer(1, 2, 5126, 0, 20, ptr)".to_string(),
+                format!("EnableVertexArrayAttrib({id}, 1)"),
+            ]
+        );
+    }
+
+    #[test]
+    fn vertex_buffer_reuses_its_storage() {
+        let context = MockGL::load();
+        let mut vbo = VertexBuffer::<f32>::new(&context);
+        let id = vbo.get_id();
+        MockGL::take_calls();
+
+        vbo.buffer_data(&[1.0, 2.0, 3.0, 4.0]);
+        assert_eq!(
+            take_call_strings(),
+            vec![
+                format!("BindBuffer(34962, {id})"),
+                "BufferData(34962, 16, ptr, 35044)".to_string(),
+            ]
+        );
+
+        vbo.buffer_data(&[5.0, 6.0]);
+        assert_eq!(take_call_strings(), vec!["BufferSubData(34962, 0, 8, ptr)"]);
+        assert_eq!(vbo.get_len(), 2);
+        assert_eq!(vbo.get_capacity(), 4);
+
+        vbo.update(1, &[7.0, 8.0]);
+        assert_eq!(take_call_strings(), vec!["BufferSubData(34962, 4, 8, ptr)"]);
+        assert_eq!(vbo.get_len(), 3);
+        assert_eq!(vbo.get_capacity(), 4);
+    }
+
+    #[test]
+    fn shader_program_unbind_uses_program_zero() {
+        let context = MockGL::load();
+        let program = ShaderProgram::new(&context);
+        program.bind();
+        assert_eq!(MockGL::get_program(), program.get_id());
+        MockGL::take_calls();
+
+        program.unbind();
+        assert_eq!(take_call_strings(), vec!["UseProgram(0)"]);
+        assert_eq!(MockGL::get_program(), 0);
+    }
+
+    #[test]
+    fn dropping_resources_deletes_their_objects() {
+        let context = MockGL::load();
+        let vao = VertexArray::new(&context);
+        let vbo = VertexBuffer::<f32>::new(&context);
+        assert!(MockGL::is_alive(MockObject::VertexArray, vao.get_id()));
+        assert_eq!(MockGL::get_live_objects(), 2);
+
+        drop(vao);
+        drop(vbo);
+        assert_eq!(MockGL::get_live_objects(), 0);
+    }
+}
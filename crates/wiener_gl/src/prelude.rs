@@ -1,5 +1,10 @@
 pub use crate::{
-    Bindable, Buffer, Drawable, ElementBuffer, FrameBuffer, GLManager, GLWindow, HasID, Mesh,
-    MeshFileHandler, MeshHandlerOBJ, MeshHandlerOFF, RenderBuffer, Shader, ShaderProgram, Texture,
-    Texture2D, UniformBuffer, VertexArray, VertexAttribute, VertexBuffer,
+    run, App, Bindable, BlendEquation, BlendFactor, Buffer, Capability, ClearMask, CompareFunc,
+    ConditionalRenderMode, DataType, DebugGroup, Drawable, ElementBuffer, Face, Fence, FrameBuffer,
+    FrontFace, GLCapabilities, GLContext, GLManager, GLWindow,
+    GLWindowBuilder, HasContext, HasID, Index, Labelable, MagFilter, Mesh, MeshFileHandler,
+    MeshHandlerOBJ, MeshHandlerOFF, MinFilter, Pod, PolygonMode, Query, QueryRing, QueryTarget,
+    RenderBuffer, RenderState, ResourceInfo, ResourceKind, ResourceStats, Shader, ShaderProgram,
+    StateCache, StencilOp, StencilState, Texture, Texture2D, TextureWrap, UniformBuffer,
+    VertexArray, VertexAttribute, VertexBuffer, Zeroable,
 };
@@ -0,0 +1,403 @@
+use crate::{ConditionalRenderMode, GLContext, HasContext, HasID, QueryTarget, ResourceKind};
+
+use gl;
+use gl::types::*;
+use log;
+use std::collections::VecDeque;
+
+/// OpenGL query object, which measures something on the GPU without
+/// stalling the CPU. Queries are not shared between contexts.
+#[derive(Debug)]
+pub struct Query {
+    _id: u32,
+    _context: GLContext,
+    _target: QueryTarget,
+}
+
+/// Scope of an active query, which ends the query when dropped.
+#[derive(Debug)]
+pub struct QueryScope<'a> {
+    _query: &'a Query,
+}
+
+/// Scope of conditional rendering, which ends it when dropped.
+#[derive(Debug)]
+pub struct ConditionalRenderScope<'a> {
+    _query: &'a Query,
+}
+
+impl HasID for Query {
+    fn get_id(&self) -> u32 {
+        return self._id;
+    }
+}
+
+impl HasContext for Query {
+    fn get_context(&self) -> &GLContext {
+        return &self._context;
+    }
+}
+
+impl Query {
+    /// Generate a new query in the given context.
+    #[track_caller]
+    pub fn new(context: &GLContext, target: QueryTarget) -> Self {
+        context.debug_assert_exactly_current("Query");
+        let mut query_id = 0;
+        unsafe {
+            gl_call!(GenQueries(1, &mut query_id));
+        }
+        log::info!("Query :: Creating new {:?} query {:?}", target, query_id);
+        context.register_resource(ResourceKind::Query, query_id);
+        return Query {
+            _id: query_id,
+            _context: context.clone(),
+            _target: target,
+        };
+    }
+
+    /// Get the kind of value measured by the query.
+    pub fn get_target(&self) -> QueryTarget {
+        return self._target;
+    }
+
+    /// Begin the query, which ends when the returned scope is dropped. Only
+    /// one query of each target can be active at a time.
+    ///
+    /// Timestamp queries can not be scoped, so use `timestamp` for those.
+    pub fn begin(&self) -> QueryScope<'_> {
+        assert!(
+            self._target != QueryTarget::Timestamp,
+            "Timestamp queries can not be scoped"
+        );
+        self._context.debug_assert_exactly_current("Query");
+        log::trace!("Query :: Beginning query {:?}", self.get_id());
+        unsafe {
+            gl_call!(BeginQuery(self._target as GLenum, self.get_id()));
+        }
+        return QueryScope { _query: self };
+    }
+
+    /// Record the GPU time once every previous command has been completed.
+    /// Only valid for timestamp queries.
+    pub fn timestamp(&self) {
+        assert!(
+            self._target == QueryTarget::Timestamp,
+            "Only timestamp queries can record a timestamp"
+        );
+        self._context.debug_assert_exactly_current("Query");
+        log::trace!("Query :: Recording timestamp {:?}", self.get_id());
+        unsafe {
+            gl_call!(QueryCounter(self.get_id(), gl::TIMESTAMP));
+        }
+    }
+
+    /// Get whether the result is available or not, without blocking.
+    pub fn is_available(&self) -> bool {
+        self._context.debug_assert_exactly_current("Query");
+        let mut available = 0;
+        unsafe {
+            gl_call!(GetQueryObjectiv(
+                self.get_id(),
+                gl::QUERY_RESULT_AVAILABLE,
+                &mut available
+            ));
+        }
+        return available != 0;
+    }
+
+    /// Get the result if it is available, without blocking. Times are given
+    /// in nanoseconds.
+    pub fn try_get_result(&self) -> Option<u64> {
+        if !self.is_available() {
+            return None;
+        }
+        return Some(self.get_result());
+    }
+
+    /// Get the result, blocking until it is available. Times are given in
+    /// nanoseconds.
+    pub fn get_result(&self) -> u64 {
+        self._context.debug_assert_exactly_current("Query");
+        let mut result = 0;
+        unsafe {
+            gl_call!(GetQueryObjectui64v(
+                self.get_id(),
+                gl::QUERY_RESULT,
+                &mut result
+            ));
+        }
+        return result;
+    }
+
+    /// Render conditionally on the result of this occlusion query, until the
+    /// returned scope is dropped. Draw calls in the scope are discarded when
+    /// no samples passed.
+    pub fn conditional_render(&self, mode: ConditionalRenderMode) -> ConditionalRenderScope<'_> {
+        assert!(
+            matches!(
+                self._target,
+                QueryTarget::SamplesPassed | QueryTarget::AnySamplesPassed
+            ),
+            "Conditional rendering requires an occlusion query"
+        );
+        self._context.debug_assert_exactly_current("Query");
+        log::trace!(
+            "Query :: Beginning conditional render on query {:?}",
+            self.get_id()
+        );
+        unsafe {
+            gl_call!(BeginConditionalRender(self.get_id(), mode as GLenum));
+        }
+        return ConditionalRenderScope { _query: self };
+    }
+}
+
+impl Drop for Query {
+    fn drop(&mut self) {
+        if !self._context.check_delete_exactly("Query") {
+            return;
+        }
+        log::info!("Query :: Deleting query {:?}", self.get_id());
+        unsafe {
+            gl_call!(DeleteQueries(1, &self.get_id()));
+        }
+        self._context
+            .unregister_resource(ResourceKind::Query, self.get_id());
+    }
+}
+
+impl<'a> QueryScope<'a> {
+    /// End the query.
+    pub fn end(self) {}
+}
+
+impl<'a> Drop for QueryScope<'a> {
+    fn drop(&mut self) {
+        log::trace!("Query :: Ending query {:?}", self._query.get_id());
+        unsafe {
+            gl_call!(EndQuery(self._query.get_target() as GLenum));
+        }
+    }
+}
+
+impl<'a> ConditionalRenderScope<'a> {
+    /// End conditional rendering.
+    pub fn end(self) {}
+}
+
+impl<'a> Drop for ConditionalRenderScope<'a> {
+    fn drop(&mut self) {
+        log::trace!(
+            "Query :: Ending conditional render on query {:?}",
+            self._query.get_id()
+        );
+        unsafe {
+            gl_call!(EndConditionalRender());
+        }
+    }
+}
+
+/// Ring of queries of the same target, used to measure something every
+/// frame without waiting for the results.
+///
+/// Queries are used in turn, results are read only once they are
+/// available, and the ring grows whenever every query is still waiting for
+/// its result.
+#[derive(Debug)]
+pub struct QueryRing {
+    _context: GLContext,
+    _target: QueryTarget,
+    _queries: Vec<Query>,
+
+    /// Indices of the queries waiting for their result, oldest first.
+    _pending: VecDeque<usize>,
+
+    /// Indices of the queries that can be reused, in the order they were
+    /// freed.
+    _free: VecDeque<usize>,
+    _latest: Option<u64>,
+}
+
+impl QueryRing {
+    /// Create a ring with `size` queries of the given target in the given
+    /// context.
+    #[track_caller]
+    pub fn new(context: &GLContext, target: QueryTarget, size: usize) -> Self {
+        log::info!("QueryRing :: Creating ring of {:?} queries", size);
+        let mut queries = Vec::with_capacity(size);
+        for _ in 0..size {
+            queries.push(Query::new(context, target));
+        }
+        return QueryRing {
+            _context: context.clone(),
+            _target: target,
+            _queries: queries,
+            _pending: VecDeque::new(),
+            _free: (0..size).collect(),
+            _latest: None,
+        };
+    }
+
+    /// Get the kind of value measured by the queries.
+    pub fn get_target(&self) -> QueryTarget {
+        return self._target;
+    }
+
+    /// Get the number of queries in the ring.
+    pub fn get_size(&self) -> usize {
+        return self._queries.len();
+    }
+
+    /// Take a free query, growing the ring if there is none.
+    fn next_query(&mut self) -> &Query {
+        let index = match self._free.pop_front() {
+            Some(index) => index,
+            None => {
+                log::debug!("QueryRing :: Every query is pending, growing the ring");
+                self._queries.push(Query::new(&self._context, self._target));
+                self._queries.len() - 1
+            }
+        };
+        self._pending.push_back(index);
+        return &self._queries[index];
+    }
+
+    /// Begin the next query, which ends when the returned scope is dropped.
+    pub fn begin(&mut self) -> QueryScope<'_> {
+        return self.next_query().begin();
+    }
+
+    /// Record a timestamp with the next query.
+    pub fn timestamp(&mut self) {
+        self.next_query().timestamp();
+    }
+
+    /// Collect the results that became available since the last poll,
+    /// oldest first, without blocking.
+    pub fn poll(&mut self) -> Vec<u64> {
+        let mut results = Vec::new();
+        while let Some(&index) = self._pending.front() {
+            match self._queries[index].try_get_result() {
+                Some(result) => {
+                    results.push(result);
+                    self._pending.pop_front();
+                    self._free.push_back(index);
+                }
+                None => break,
+            }
+        }
+        if let Some(&result) = results.last() {
+            self._latest = Some(result);
+        }
+        return results;
+    }
+
+    /// Get the most recent result collected by `poll`.
+    pub fn get_latest(&self) -> Option<u64> {
+        return self._latest;
+    }
+}
+
+#[cfg(all(test, feature = "gl-mock"))]
+mod tests {
+    use super::*;
+    use crate::mock::take_call_strings;
+    use crate::MockGL;
+
+    /// Get the ids of the queries begun by the recorded calls.
+    fn begun_queries() -> Vec<String> {
+        return take_call_strings()
+            .into_iter()
+            .filter(|c| c.starts_with("BeginQuery"))
+            .collect();
+    }
+
+    #[test]
+    fn ring_uses_the_queries_in_turn() {
+        let context = MockGL::load();
+        let mut ring = QueryRing::new(&context, QueryTarget::TimeElapsed, 2);
+        MockGL::take_calls();
+
+        for _ in 0..4 {
+            ring.begin().end();
+            ring.poll();
+        }
+        assert_eq!(ring.get_size(), 2);
+        assert_eq!(
+            begun_queries(),
+            vec![
+                "BeginQuery(35007, 1)",
+                "BeginQuery(35007, 2)",
+                "BeginQuery(35007, 1)",
+                "BeginQuery(35007, 2)",
+            ]
+        );
+    }
+
+    #[test]
+    fn ring_wraps_around_while_results_are_late() {
+        let context = MockGL::load();
+        let mut ring = QueryRing::new(&context, QueryTarget::TimeElapsed, 3);
+        MockGL::take_calls();
+
+        // Each result arrives one frame after its query ends
+        MockGL::set_query_result(1, None);
+        ring.begin().end();
+        assert!(ring.poll().is_empty());
+        for frame in 1..6 {
+            let previous = (frame - 1) % 3 + 1;
+            let current = frame % 3 + 1;
+            MockGL::set_query_result(current, None);
+            ring.begin().end();
+            MockGL::set_query_result(previous, Some(frame as u64 * 100));
+            assert_eq!(ring.poll(), vec![frame as u64 * 100]);
+        }
+        assert_eq!(ring.get_size(), 3);
+        assert_eq!(ring.get_latest(), Some(500));
+        assert_eq!(
+            begun_queries(),
+            vec![
+                "BeginQuery(35007, 1)",
+                "BeginQuery(35007, 2)",
+                "BeginQuery(35007, 3)",
+                "BeginQuery(35007, 1)",
+                "BeginQuery(35007, 2)",
+                "BeginQuery(35007, 3)",
+            ]
+        );
+    }
+
+    #[test]
+    fn ring_grows_when_every_query_is_pending() {
+        let context = MockGL::load();
+        let mut ring = QueryRing::new(&context, QueryTarget::Timestamp, 2);
+        for id in 1..=3 {
+            MockGL::set_query_result(id, None);
+        }
+
+        for _ in 0..3 {
+            ring.timestamp();
+            assert!(ring.poll().is_empty());
+        }
+        assert_eq!(ring.get_size(), 3);
+        assert_eq!(ring.get_latest(), None);
+    }
+
+    #[test]
+    fn poll_returns_results_in_order_up_to_the_first_pending_one() {
+        let context = MockGL::load();
+        let mut ring = QueryRing::new(&context, QueryTarget::Timestamp, 3);
+        MockGL::set_query_result(1, Some(10));
+        MockGL::set_query_result(2, None);
+        MockGL::set_query_result(3, Some(30));
+        for _ in 0..3 {
+            ring.timestamp();
+        }
+
+        assert_eq!(ring.poll(), vec![10]);
+        MockGL::set_query_result(2, Some(20));
+        assert_eq!(ring.poll(), vec![20, 30]);
+        assert_eq!(ring.get_latest(), Some(30));
+    }
+}
@@ -0,0 +1,204 @@
+use crate::ResourceKind;
+
+use gl;
+use gl::types::*;
+use std::collections::HashMap;
+use std::fmt;
+use std::panic::Location;
+
+/// Resource alive in a context, as recorded by its registry.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ResourceInfo {
+    /// Kind of resource.
+    pub kind: ResourceKind,
+
+    /// OpenGL ID of the resource. Fences have no ID, so they are numbered
+    /// in the order they were created instead.
+    pub id: u32,
+
+    /// Estimated size in GPU memory, in bytes.
+    pub size: usize,
+
+    /// Debug name given with `Labelable`, if any.
+    pub label: Option<String>,
+
+    /// Place in the source code where the resource was created.
+    pub location: &'static Location<'static>,
+}
+
+impl fmt::Display for ResourceInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} {:?}", self.kind, self.id)?;
+        if let Some(label) = &self.label {
+            write!(f, " {:?}", label)?;
+        }
+        return write!(f, " ({:?} bytes) created at {}", self.size, self.location);
+    }
+}
+
+/// Snapshot of the number and size of the resources alive in a context.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ResourceStats {
+    _counts: HashMap<ResourceKind, usize>,
+    _bytes: HashMap<ResourceKind, usize>,
+}
+
+impl ResourceStats {
+    /// Count the given resources.
+    pub(crate) fn from_resources(resources: &[ResourceInfo]) -> Self {
+        let mut stats = ResourceStats::default();
+        for info in resources {
+            *stats._counts.entry(info.kind).or_insert(0) += 1;
+            *stats._bytes.entry(info.kind).or_insert(0) += info.size;
+        }
+        return stats;
+    }
+
+    /// Get the number of resources of a kind.
+    pub fn get_count(&self, kind: ResourceKind) -> usize {
+        return self._counts.get(&kind).copied().unwrap_or(0);
+    }
+
+    /// Get the estimated size in bytes of the resources of a kind.
+    pub fn get_bytes(&self, kind: ResourceKind) -> usize {
+        return self._bytes.get(&kind).copied().unwrap_or(0);
+    }
+
+    /// Get the number of resources of every kind.
+    pub fn get_total_count(&self) -> usize {
+        return self._counts.values().sum();
+    }
+
+    /// Get the estimated size in bytes of the resources of every kind.
+    pub fn get_total_bytes(&self) -> usize {
+        return self._bytes.values().sum();
+    }
+}
+
+/// Registry of the resources alive in a context.
+#[derive(Debug, Default)]
+pub(crate) struct ResourceRegistry {
+    _resources: HashMap<(ResourceKind, u32), ResourceInfo>,
+}
+
+impl ResourceRegistry {
+    pub fn register(&mut self, kind: ResourceKind, id: u32, location: &'static Location<'static>) {
+        self._resources.insert(
+            (kind, id),
+            ResourceInfo {
+                kind,
+                id,
+                size: 0,
+                label: None,
+                location,
+            },
+        );
+    }
+
+    pub fn unregister(&mut self, kind: ResourceKind, id: u32) {
+        self._resources.remove(&(kind, id));
+    }
+
+    pub fn set_size(&mut self, kind: ResourceKind, id: u32, size: usize) {
+        if let Some(info) = self._resources.get_mut(&(kind, id)) {
+            info.size = size;
+        }
+    }
+
+    pub fn set_label(&mut self, kind: ResourceKind, id: u32, label: &str) {
+        if let Some(info) = self._resources.get_mut(&(kind, id)) {
+            info.label = Some(label.to_string());
+        }
+    }
+
+    /// Get every resource, sorted by kind and id.
+    pub fn get_resources(&self) -> Vec<ResourceInfo> {
+        let mut resources: Vec<ResourceInfo> = self._resources.values().cloned().collect();
+        resources.sort_by_key(|r| (r.kind as GLenum, r.id));
+        return resources;
+    }
+}
+
+/// Estimate the size in bytes of a pixel with the given internal format.
+pub(crate) fn get_pixel_size(internal_format: GLenum) -> usize {
+    return match internal_format {
+        gl::RED | gl::R8 | gl::STENCIL_INDEX8 => 1,
+        gl::RG | gl::RG8 | gl::R16 | gl::R16F | gl::DEPTH_COMPONENT16 => 2,
+        gl::RGB | gl::RGB8 | gl::SRGB8 => 3,
+        gl::RG16 | gl::RG16F | gl::R32F | gl::R32I | gl::R32UI => 4,
+        gl::RGB16 | gl::RGB16F => 6,
+        gl::RGBA16 | gl::RGBA16F | gl::RG32F | gl::DEPTH32F_STENCIL8 => 8,
+        gl::RGB32F => 12,
+        gl::RGBA32F => 16,
+        _ => 4,
+    };
+}
+
+/// Estimate the size in bytes of an image with a full chain of mipmaps,
+/// which take a third of the size of the base level.
+pub(crate) fn get_mipmapped_size(width: i32, height: i32, internal_format: GLenum) -> usize {
+    let base = (width.max(0) * height.max(0)) as usize * get_pixel_size(internal_format);
+    return base * 4 / 3;
+}
+
+#[cfg(all(test, feature = "gl-mock"))]
+mod tests {
+    use super::*;
+    use crate::{
+        Buffer, ElementBuffer, Fence, HasID, MockGL, Query, QueryTarget, Shader, ShaderProgram,
+        VertexBuffer,
+    };
+
+    #[test]
+    fn stats_count_resources_by_kind() {
+        let context = MockGL::load();
+        let mut vbo = VertexBuffer::<f32>::new(&context);
+        vbo.buffer_data(&[0.0; 6]);
+        let mut ebo = ElementBuffer::<u16>::new(&context);
+        ebo.buffer_data(&[0, 1, 2]);
+        let shader = Shader::new(&context, "", gl::VERTEX_SHADER);
+        let program = ShaderProgram::new(&context);
+        let query = Query::new(&context, QueryTarget::TimeElapsed);
+        let fence = Fence::new(&context);
+
+        let stats = context.get_resource_stats();
+        assert_eq!(stats.get_count(ResourceKind::Buffer), 2);
+        assert_eq!(stats.get_bytes(ResourceKind::Buffer), 30);
+        assert_eq!(stats.get_count(ResourceKind::Shader), 1);
+        assert_eq!(stats.get_count(ResourceKind::Program), 1);
+        assert_eq!(stats.get_count(ResourceKind::Query), 1);
+        assert_eq!(stats.get_count(ResourceKind::Sync), 1);
+        assert_eq!(stats.get_count(ResourceKind::Texture), 0);
+        assert_eq!(stats.get_total_count(), 6);
+        assert_eq!(stats.get_total_bytes(), 30);
+
+        drop(vbo);
+        drop(shader);
+        drop(query);
+        drop(fence);
+        let stats = context.get_resource_stats();
+        assert_eq!(stats.get_count(ResourceKind::Buffer), 1);
+        assert_eq!(stats.get_bytes(ResourceKind::Buffer), 6);
+        assert_eq!(stats.get_total_count(), 2);
+        assert_eq!(context.log_leaks(), 2);
+
+        drop(ebo);
+        drop(program);
+        assert_eq!(context.get_resource_stats(), ResourceStats::default());
+        assert_eq!(context.log_leaks(), 0);
+    }
+
+    #[test]
+    fn resources_remember_where_they_were_created() {
+        let context = MockGL::load();
+        let line = line!() + 1;
+        let query = Query::new(&context, QueryTarget::SamplesPassed);
+
+        let resources = context.get_resources();
+        assert_eq!(resources.len(), 1);
+        assert_eq!(resources[0].kind, ResourceKind::Query);
+        assert_eq!(resources[0].id, query.get_id());
+        assert_eq!(resources[0].location.file(), file!());
+        assert_eq!(resources[0].location.line(), line);
+    }
+}
@@ -0,0 +1,182 @@
+use crate::{BlendFactor, CompareFunc, Face, PolygonMode, StateCache, StencilOp};
+
+use log;
+
+/// Configuration of the stencil test.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StencilState {
+    /// Function used to compare the reference against the stencil buffer.
+    pub func: CompareFunc,
+
+    /// Reference value of the test.
+    pub reference: i32,
+
+    /// Mask applied to both the reference and the stored value before
+    /// comparing them.
+    pub read_mask: u32,
+
+    /// Mask of the bits that can be written to the stencil buffer.
+    pub write_mask: u32,
+
+    /// Action taken when the stencil test fails.
+    pub stencil_fail: StencilOp,
+
+    /// Action taken when the stencil test passes but the depth test fails.
+    pub depth_fail: StencilOp,
+
+    /// Action taken when both the stencil and depth tests pass.
+    pub pass: StencilOp,
+}
+
+impl StencilState {
+    /// Set the comparison function and reference value, returning `self`.
+    pub fn func(mut self, func: CompareFunc, reference: i32) -> Self {
+        self.func = func;
+        self.reference = reference;
+        return self;
+    }
+
+    /// Set the read and write masks, returning `self`.
+    pub fn masks(mut self, read_mask: u32, write_mask: u32) -> Self {
+        self.read_mask = read_mask;
+        self.write_mask = write_mask;
+        return self;
+    }
+
+    /// Set the actions taken after the tests, returning `self`.
+    pub fn ops(mut self, stencil_fail: StencilOp, depth_fail: StencilOp, pass: StencilOp) -> Self {
+        self.stencil_fail = stencil_fail;
+        self.depth_fail = depth_fail;
+        self.pass = pass;
+        return self;
+    }
+}
+
+impl Default for StencilState {
+    fn default() -> Self {
+        return StencilState {
+            func: CompareFunc::Always,
+            reference: 0,
+            read_mask: u32::MAX,
+            write_mask: u32::MAX,
+            stencil_fail: StencilOp::Keep,
+            depth_fail: StencilOp::Keep,
+            pass: StencilOp::Keep,
+        };
+    }
+}
+
+/// Description of the fixed function state used when drawing.
+///
+/// The default value matches the initial OpenGL state. Applying a render
+/// state only issues the calls for the parts that differ from the last
+/// applied one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RenderState {
+    /// Whether the depth test is enabled or not.
+    pub depth_test: bool,
+
+    /// Function used by the depth test.
+    pub depth_func: CompareFunc,
+
+    /// Whether fragments write to the depth buffer or not.
+    pub depth_write: bool,
+
+    /// Source and destination blending factors, or `None` to disable
+    /// blending.
+    pub blend: Option<(BlendFactor, BlendFactor)>,
+
+    /// Face to cull, or `None` to disable culling.
+    pub cull_face: Option<Face>,
+
+    /// Way polygons are rasterized, for both faces.
+    pub polygon_mode: PolygonMode,
+
+    /// Scissor box given as (x, y, width, height), or `None` to disable the
+    /// scissor test.
+    pub scissor: Option<(i32, i32, i32, i32)>,
+
+    /// Stencil test configuration, or `None` to disable the stencil test.
+    pub stencil: Option<StencilState>,
+
+    /// Whether the red, green, blue and alpha channels are written or not.
+    pub color_mask: [bool; 4],
+}
+
+impl RenderState {
+    /// Enable or disable the depth test, returning `self`.
+    pub fn depth_test(mut self, enabled: bool) -> Self {
+        self.depth_test = enabled;
+        return self;
+    }
+
+    /// Set the depth function, returning `self`.
+    pub fn depth_func(mut self, func: CompareFunc) -> Self {
+        self.depth_func = func;
+        return self;
+    }
+
+    /// Enable or disable writing to the depth buffer, returning `self`.
+    pub fn depth_write(mut self, enabled: bool) -> Self {
+        self.depth_write = enabled;
+        return self;
+    }
+
+    /// Set the blending factors, returning `self`.
+    pub fn blend(mut self, blend: Option<(BlendFactor, BlendFactor)>) -> Self {
+        self.blend = blend;
+        return self;
+    }
+
+    /// Set the culled face, returning `self`.
+    pub fn cull_face(mut self, face: Option<Face>) -> Self {
+        self.cull_face = face;
+        return self;
+    }
+
+    /// Set the polygon mode, returning `self`.
+    pub fn polygon_mode(mut self, mode: PolygonMode) -> Self {
+        self.polygon_mode = mode;
+        return self;
+    }
+
+    /// Set the scissor box, returning `self`.
+    pub fn scissor(mut self, scissor: Option<(i32, i32, i32, i32)>) -> Self {
+        self.scissor = scissor;
+        return self;
+    }
+
+    /// Set the stencil test configuration, returning `self`.
+    pub fn stencil(mut self, stencil: Option<StencilState>) -> Self {
+        self.stencil = stencil;
+        return self;
+    }
+
+    /// Set the color mask, returning `self`.
+    pub fn color_mask(mut self, red: bool, green: bool, blue: bool, alpha: bool) -> Self {
+        self.color_mask = [red, green, blue, alpha];
+        return self;
+    }
+
+    /// Apply the render state to the current context.
+    pub fn apply(&self) {
+        log::trace!("RenderState :: Applying {:?}", self);
+        StateCache::apply_render_state(self);
+    }
+}
+
+impl Default for RenderState {
+    fn default() -> Self {
+        return RenderState {
+            depth_test: false,
+            depth_func: CompareFunc::Less,
+            depth_write: true,
+            blend: None,
+            cull_face: None,
+            polygon_mode: PolygonMode::Fill,
+            scissor: None,
+            stencil: None,
+            color_mask: [true; 4],
+        };
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{Bindable, HasID};
+use crate::{Bindable, GLContext, HasContext, HasID, Labelable, ResourceKind, StateCache};
 
 use gl;
 use gl::types::*;
@@ -42,18 +42,20 @@ fn get_shader_type(file_extension: &str) -> GLenum {
 }
 
 /// OpenGL shader component.
-#[derive(Clone, Copy, Debug)]
+#[derive(Debug)]
 pub struct Shader {
     _id: u32,
+    _context: GLContext,
 
     /// Type of shader.
     _type: GLenum,
 }
 
 /// Program that contains a bunch of compiled shaders.
-#[derive(Clone, Copy, Debug)]
+#[derive(Debug)]
 pub struct ShaderProgram<'a> {
     _id: u32,
+    _context: GLContext,
     _shaders: &'a [Shader],
 }
 
@@ -63,63 +65,86 @@ impl HasID for Shader {
     }
 }
 
+impl HasContext for Shader {
+    fn get_context(&self) -> &GLContext {
+        return &self._context;
+    }
+}
+
 impl Shader {
-    /// Create a new shader.
-    pub fn new(content: &str, shader_type: GLenum) -> Self {
+    /// Create a new shader in the given context.
+    #[track_caller]
+    pub fn new(context: &GLContext, content: &str, shader_type: GLenum) -> Self {
+        context.debug_assert_current("Shader");
         let shader_id;
         let mut success = 0;
         unsafe {
             // Compile the shader
-            shader_id = gl::CreateShader(shader_type);
+            shader_id = gl_call!(CreateShader(shader_type));
             log::info!("Shader :: Creating new shader {:?}", shader_id);
-            gl::ShaderSource(
+            gl_call!(ShaderSource(
                 shader_id,
                 1,
                 &(content.as_bytes().as_ptr().cast()),
                 &(content.len().try_into().unwrap()),
-            );
-            gl::CompileShader(shader_id);
+            ));
+            gl_call!(CompileShader(shader_id));
 
             // Verify compilation
-            gl::GetShaderiv(shader_id, gl::COMPILE_STATUS, &mut success);
+            gl_call!(GetShaderiv(shader_id, gl::COMPILE_STATUS, &mut success));
             if success == 0 {
                 let mut v: Vec<u8> = Vec::with_capacity(1024);
                 let mut log_len = 0_i32;
-                gl::GetShaderInfoLog(shader_id, 1024, &mut log_len, v.as_mut_ptr().cast());
+                gl_call!(GetShaderInfoLog(
+                    shader_id,
+                    1024,
+                    &mut log_len,
+                    v.as_mut_ptr().cast()
+                ));
                 v.set_len(log_len.try_into().unwrap());
                 panic!("Failed to compile shader : {}", String::from_utf8_lossy(&v));
             }
         }
+        context.register_resource(ResourceKind::Shader, shader_id);
         return Shader {
             _id: shader_id,
+            _context: context.clone(),
             _type: shader_type,
         };
     }
 
     /// Create a new shader from a file, assuming the shader type from the file extension.
-    pub fn from_file(filename: &str) -> Self {
+    #[track_caller]
+    pub fn from_file(context: &GLContext, filename: &str) -> Self {
         let shader_content = fs::read_to_string(filename)
             .expect(format!("Error reading file {:?}.", filename).as_str());
         let file_extension = filename
             .split(".")
             .last()
             .expect("Couldn't find file extension.");
-        return Shader::new(&shader_content, get_shader_type(file_extension));
+        return Shader::new(context, &shader_content, get_shader_type(file_extension));
     }
 
     /// Create a new shader from a file, explicitly giving the shader type.
-    pub fn from_file_explicit(filename: &str, shader_type: GLenum) -> Self {
+    #[track_caller]
+    pub fn from_file_explicit(context: &GLContext, filename: &str, shader_type: GLenum) -> Self {
         let shader_content = fs::read_to_string(filename)
             .expect(format!("Error reading file {:?}.", filename).as_str());
-        return Shader::new(&shader_content, shader_type);
+        return Shader::new(context, &shader_content, shader_type);
     }
+}
 
-    /// Delete the shader.
-    pub fn delete(&self) {
+impl Drop for Shader {
+    fn drop(&mut self) {
+        if !self._context.check_delete("Shader") {
+            return;
+        }
         log::info!("Shader :: Deleting shader");
         unsafe {
-            gl::DeleteShader(self.get_id());
+            gl_call!(DeleteShader(self.get_id()));
         }
+        self._context
+            .unregister_resource(ResourceKind::Shader, self.get_id());
     }
 }
 
@@ -129,23 +154,40 @@ impl<'a> HasID for ShaderProgram<'a> {
     }
 }
 
+impl<'a> HasContext for ShaderProgram<'a> {
+    fn get_context(&self) -> &GLContext {
+        return &self._context;
+    }
+}
+
+impl<'a> Labelable for ShaderProgram<'a> {
+    fn get_resource_kind(&self) -> ResourceKind {
+        return ResourceKind::Program;
+    }
+}
+
 impl<'a> ShaderProgram<'a> {
-    pub fn new() -> Self {
+    #[track_caller]
+    pub fn new(context: &GLContext) -> Self {
+        context.debug_assert_current("ShaderProgram");
         unsafe {
-            let program_id = gl::CreateProgram();
+            let program_id = gl_call!(CreateProgram());
             log::info!(
                 "ShaderProgram :: Creating new shader program {:?}",
                 program_id
             );
+            context.register_resource(ResourceKind::Program, program_id);
             return ShaderProgram {
                 _id: program_id,
+                _context: context.clone(),
                 _shaders: &[],
             };
         }
     }
 
-    pub fn from_array(shaders: &'a [Shader]) -> Self {
-        return Self::new().shaders(shaders);
+    #[track_caller]
+    pub fn from_array(context: &GLContext, shaders: &'a [Shader]) -> Self {
+        return Self::new(context).shaders(shaders);
     }
 
     pub fn shaders(mut self, shaders: &'a [Shader]) -> Self {
@@ -154,19 +196,23 @@ impl<'a> ShaderProgram<'a> {
 
         for shader in self._shaders {
             unsafe {
-                gl::AttachShader(self.get_id(), shader.get_id());
-                gl::LinkProgram(self.get_id());
+                gl_call!(AttachShader(self.get_id(), shader.get_id()));
+                gl_call!(LinkProgram(self.get_id()));
                 let mut success = 0;
-                gl::GetProgramiv(self.get_id(), gl::LINK_STATUS, &mut success);
+                gl_call!(GetProgramiv(self.get_id(), gl::LINK_STATUS, &mut success));
                 if success == 0 {
                     let mut v: Vec<u8> = Vec::with_capacity(1024);
                     let mut log_len = 0_i32;
-                    gl::GetProgramInfoLog(self.get_id(), 1024, &mut log_len, v.as_mut_ptr().cast());
+                    gl_call!(GetProgramInfoLog(
+                        self.get_id(),
+                        1024,
+                        &mut log_len,
+                        v.as_mut_ptr().cast()
+                    ));
                     v.set_len(log_len.try_into().unwrap());
                     panic!("Program Link Error: {}", String::from_utf8_lossy(&v));
                 };
             }
-            shader.delete();
         }
         return self;
     }
@@ -174,155 +220,167 @@ impl<'a> ShaderProgram<'a> {
     pub fn get_uniform_location(&self, name: &str) -> GLint {
         unsafe {
             // Strings in rust are not null terminated, so we terminate them manually.
-            return gl::GetUniformLocation(
+            return gl_call!(GetUniformLocation(
                 self.get_id(),
                 format!("{name}\0").as_ptr() as *const GLchar,
-            );
+            ));
         }
     }
 
     pub fn uniform_1i<T: num::PrimInt>(&self, name: &str, val0: T) {
         self.bind();
         unsafe {
-            gl::Uniform1i(self.get_uniform_location(name), val0.to_i32().unwrap());
+            gl_call!(Uniform1i(
+                self.get_uniform_location(name),
+                val0.to_i32().unwrap()
+            ));
         }
     }
 
     pub fn uniform_2i<T: num::PrimInt>(&self, name: &str, val0: T, val1: T) {
         self.bind();
         unsafe {
-            gl::Uniform2i(
+            gl_call!(Uniform2i(
                 self.get_uniform_location(name),
                 val0.to_i32().unwrap(),
                 val1.to_i32().unwrap(),
-            );
+            ));
         }
     }
 
     pub fn uniform_3i<T: num::PrimInt>(&self, name: &str, val0: T, val1: T, val2: T) {
         self.bind();
         unsafe {
-            gl::Uniform3i(
+            gl_call!(Uniform3i(
                 self.get_uniform_location(name),
                 val0.to_i32().unwrap(),
                 val1.to_i32().unwrap(),
                 val2.to_i32().unwrap(),
-            );
+            ));
         }
     }
 
     pub fn uniform_4i<T: num::PrimInt>(&self, name: &str, val0: T, val1: T, val2: T, val3: T) {
         self.bind();
         unsafe {
-            gl::Uniform4i(
+            gl_call!(Uniform4i(
                 self.get_uniform_location(name),
                 val0.to_i32().unwrap(),
                 val1.to_i32().unwrap(),
                 val2.to_i32().unwrap(),
                 val3.to_i32().unwrap(),
-            );
+            ));
         }
     }
 
     pub fn uniform_1f<T: num::Float>(&self, name: &str, val0: T) {
         self.bind();
         unsafe {
-            gl::Uniform1f(self.get_uniform_location(name), val0.to_f32().unwrap());
+            gl_call!(Uniform1f(
+                self.get_uniform_location(name),
+                val0.to_f32().unwrap()
+            ));
         }
     }
 
     pub fn uniform_2f<T: num::Float>(&self, name: &str, val0: T, val1: T) {
         self.bind();
         unsafe {
-            gl::Uniform2f(
+            gl_call!(Uniform2f(
                 self.get_uniform_location(name),
                 val0.to_f32().unwrap(),
                 val1.to_f32().unwrap(),
-            );
+            ));
         }
     }
 
     pub fn uniform_3f<T: num::Float>(&self, name: &str, val0: T, val1: T, val2: T) {
         self.bind();
         unsafe {
-            gl::Uniform3f(
+            gl_call!(Uniform3f(
                 self.get_uniform_location(name),
                 val0.to_f32().unwrap(),
                 val1.to_f32().unwrap(),
                 val2.to_f32().unwrap(),
-            );
+            ));
         }
     }
 
     pub fn uniform_4f<T: num::Float>(&self, name: &str, val0: T, val1: T, val2: T, val3: T) {
         self.bind();
         unsafe {
-            gl::Uniform4f(
+            gl_call!(Uniform4f(
                 self.get_uniform_location(name),
                 val0.to_f32().unwrap(),
                 val1.to_f32().unwrap(),
                 val2.to_f32().unwrap(),
                 val3.to_f32().unwrap(),
-            );
+            ));
         }
     }
 
     pub fn uniform_mat2f<T>(&self, name: &str, val: [[T; 2]; 2]) {
         self.bind();
         unsafe {
-            gl::UniformMatrix2fv(
+            gl_call!(UniformMatrix2fv(
                 self.get_uniform_location(name),
                 1,
                 gl::TRUE,
                 val[0].as_ptr() as *const f32,
-            )
+            ))
         }
     }
 
     pub fn uniform_mat3f<T>(&self, name: &str, val: [[T; 3]; 3]) {
         self.bind();
         unsafe {
-            gl::UniformMatrix3fv(
+            gl_call!(UniformMatrix3fv(
                 self.get_uniform_location(name),
                 1,
                 gl::TRUE,
                 val[0].as_ptr() as *const f32,
-            )
+            ))
         }
     }
 
     pub fn uniform_mat4f<T>(&self, name: &str, val: [[T; 4]; 4]) {
         self.bind();
         unsafe {
-            gl::UniformMatrix4fv(
+            gl_call!(UniformMatrix4fv(
                 self.get_uniform_location(name),
                 1,
                 gl::TRUE,
                 val[0].as_ptr() as *const f32,
-            )
+            ))
         }
     }
 }
 
 impl<'a> Bindable for ShaderProgram<'a> {
     fn bind(&self) {
+        self._context.debug_assert_current("ShaderProgram");
         log::trace!("ShaderProgram :: Binding");
-        unsafe {
-            gl::UseProgram(self.get_id());
-        }
+        StateCache::use_program(self.get_id());
     }
 
     fn unbind(&self) {
+        self._context.debug_assert_current("ShaderProgram");
         log::trace!("ShaderProgram :: Unbinding");
-        unsafe {
-            gl::UseProgram(self.get_id());
-        }
+        StateCache::use_program(0);
     }
+}
 
-    fn delete(&self) {
+impl<'a> Drop for ShaderProgram<'a> {
+    fn drop(&mut self) {
+        if !self._context.check_delete("ShaderProgram") {
+            return;
+        }
         log::info!("ShaderProgram :: Deleting");
+        StateCache::forget_program(self.get_id());
         unsafe {
-            gl::DeleteProgram(self.get_id());
+            gl_call!(DeleteProgram(self.get_id()));
         }
+        self._context
+            .unregister_resource(ResourceKind::Program, self.get_id());
     }
 }
@@ -1,5 +1,6 @@
 use num::{traits::Pow, Float, ToPrimitive};
 use std::ops::AddAssign;
+use std::rc::Rc;
 use std::str::FromStr;
 use std::{ffi::c_void, fmt::Debug};
 
@@ -11,16 +12,17 @@ use log::{info, trace};
 use wiener_utils::math;
 
 /// Structure for a simple mesh, corresponding to the most basic set of
-/// triangles.
-#[derive(Clone, Debug)]
+/// triangles. The vertex array and buffers are owned by the mesh, while the
+/// shader program and textures are shared with `Rc`.
+#[derive(Debug)]
 pub struct Mesh<'a, U, I> {
     pub vao: VertexArray<'a>,
-    pub vbo: VertexBuffer,
-    pub ebo: ElementBuffer,
+    pub vbo: VertexBuffer<U>,
+    pub ebo: ElementBuffer<I>,
     pub primitive: GLenum,
-    _primitive_num: i32,
-    pub shader: &'a ShaderProgram<'a>,
-    pub textures: &'a [Texture2D],
+    pub shader: Rc<ShaderProgram<'a>>,
+    pub textures: Vec<Rc<Texture2D>>,
+    pub render_state: Option<RenderState>,
     pub model_mat: [[U; 4]; 4],
     pub view_mat: [[U; 4]; 4],
     pub projection_mat: [[U; 4]; 4],
@@ -29,28 +31,33 @@ pub struct Mesh<'a, U, I> {
 
 impl<
         'a,
-        U: Float + From<f32> + FromStr + AddAssign<U> + Pow<u16, Output = U>,
-        I: FromStr + ToPrimitive + Copy,
+        U: Pod + Float + From<f32> + FromStr + AddAssign<U> + Pow<u16, Output = U>,
+        I: Index + FromStr + ToPrimitive,
     > Mesh<'a, U, I>
 where
     <U as FromStr>::Err: Debug,
     <I as FromStr>::Err: Debug,
 {
-    /// Create a new mesh associated to a shader program.
-    pub fn new(shader: &'a ShaderProgram<'a>) -> Self {
+    /// Create a new mesh associated to a shader program. The vertex array
+    /// and buffers are created in the context of the shader program.
+    #[track_caller]
+    pub fn new(shader: Rc<ShaderProgram<'a>>) -> Self {
         info!("Mesh :: Creating mesh");
+        let context = shader.get_context();
         let size = std::mem::size_of::<U>();
         info!("Mesh :: Setting associated VAO size to {:?}", size);
-        let vao = VertexArray::default().size(size as u32);
+        let vao = VertexArray::new(context).size(size as u32);
         vao.bind();
+        let vbo = VertexBuffer::new(context);
+        let ebo = ElementBuffer::new(context);
         return Mesh {
             vao,
-            vbo: VertexBuffer::new(),
-            ebo: ElementBuffer::new(),
+            vbo,
+            ebo,
             primitive: gl::TRIANGLES,
-            _primitive_num: 0,
             shader,
-            textures: &[],
+            textures: Vec::new(),
+            render_state: None,
             model_mat: math::linalg::eye4::<U>(),
             view_mat: math::linalg::eye4::<U>(),
             projection_mat: math::linalg::eye4::<U>(),
@@ -58,13 +65,14 @@ where
         };
     }
 
-    pub fn from_file(filename: &str, shader: &'a ShaderProgram<'a>) -> Self {
+    #[track_caller]
+    pub fn from_file(filename: &str, shader: Rc<ShaderProgram<'a>>) -> Self {
         let file_extension = filename
             .split(".")
             .last()
             .expect("Error reading file")
             .to_lowercase();
-        match file_extension.as_str() {
+        let mesh = match file_extension.as_str() {
             "mtl" => Self::from_handler(MeshHandlerOBJ::new(filename), shader),
             "obj" => Self::from_handler(MeshHandlerOBJ::new(filename), shader),
             "off" => Self::from_handler(MeshHandlerOFF::new(filename), shader),
@@ -72,10 +80,13 @@ where
                 "Could not interpret format from extension '.{:?}' or it is not implemented",
                 file_extension
             ),
-        }
+        };
+        mesh.set_label(filename);
+        return mesh;
     }
 
-    pub fn from_handler<T: MeshFileHandler>(handler: T, shader: &'a ShaderProgram<'a>) -> Self {
+    #[track_caller]
+    pub fn from_handler<T: MeshFileHandler>(handler: T, shader: Rc<ShaderProgram<'a>>) -> Self {
         log::debug!("Mesh :: Reading from {:?} handler", T::get_name());
         let (vertices, faces, vert_num) = handler.load_file::<U, I>();
 
@@ -93,31 +104,38 @@ where
     }
 
     /// Buffer vertices to the associated VBO, returning `self`.
-    pub fn vertices<T>(mut self, new_vertices: &[T]) -> Self {
+    pub fn vertices(mut self, new_vertices: &[U]) -> Self {
         self.set_vertices(new_vertices);
         return self;
     }
 
     /// Buffer indices to the associated EBO, returning `self`.
-    pub fn indices<T>(mut self, new_indices: &[T]) -> Self {
+    pub fn indices(mut self, new_indices: &[I]) -> Self {
         self.set_indices(new_indices);
         return self;
     }
 
     /// Set the associated shader program, returning `self`.
-    pub fn shader(mut self, new_shader: &'a ShaderProgram<'a>) -> Self {
+    pub fn shader(mut self, new_shader: Rc<ShaderProgram<'a>>) -> Self {
         trace!("Mesh :: Setting shader");
         self.shader = new_shader;
         return self;
     }
 
     /// Set the associated textures, returning `self`.
-    pub fn textures(mut self, new_textures: &'a [Texture2D]) -> Self {
+    pub fn textures(mut self, new_textures: Vec<Rc<Texture2D>>) -> Self {
         trace!("Mesh :: Setting textures");
         self.textures = new_textures;
         return self;
     }
 
+    /// Set the render state applied before drawing, returning `self`.
+    pub fn render_state(mut self, new_state: RenderState) -> Self {
+        trace!("Mesh :: Setting render state");
+        self.render_state = Some(new_state);
+        return self;
+    }
+
     /// Set the usage of the mesh, returning `self`.
     pub fn usage(mut self, new_usage: GLenum) -> Self {
         trace!("Mesh :: Setting usage");
@@ -140,23 +158,43 @@ where
     }
 
     /// Buffer vertices to the associated VBO inplace, without returning anything.
-    pub fn set_vertices<T>(&mut self, new_vertices: &[T]) {
+    pub fn set_vertices(&mut self, new_vertices: &[U]) {
         trace!("Mesh :: Setting vertices");
         self.vbo.buffer_data(new_vertices);
     }
 
     /// Buffer indices to the associated EBO inplace, without returning anything.
-    pub fn set_indices<T>(&mut self, new_indices: &[T]) {
+    pub fn set_indices(&mut self, new_indices: &[I]) {
         trace!("Mesh :: Setting indices");
         self.ebo.buffer_data(new_indices);
-        self._primitive_num =
-            (new_indices.len() * std::mem::size_of::<T>() / std::mem::size_of::<I>()) as i32;
         info!(
             "Mesh :: Setting EBO number of primitives to {:?}",
-            self._primitive_num
+            self.ebo.get_len()
         );
     }
 
+    /// Overwrite the vertex data starting at the given element of the VBO,
+    /// reusing its storage when possible. Useful for animated meshes.
+    pub fn update_vertices(&mut self, offset: usize, new_vertices: &[U]) {
+        trace!("Mesh :: Updating vertices");
+        self.vbo.update(offset, new_vertices);
+    }
+
+    /// Overwrite the indices starting at the given element of the EBO,
+    /// reusing its storage when possible.
+    pub fn update_indices(&mut self, offset: usize, new_indices: &[I]) {
+        trace!("Mesh :: Updating indices");
+        self.ebo.update(offset, new_indices);
+    }
+
+    /// Set the debug names of the vertex array and buffers, derived from the
+    /// given name.
+    pub fn set_label(&self, label: &str) {
+        self.vao.set_label(&format!("{label} (vertex array)"));
+        self.vbo.set_label(&format!("{label} (vertex buffer)"));
+        self.ebo.set_label(&format!("{label} (element buffer)"));
+    }
+
     /// Set the usage of the mesh inplace, without returning anything.
     pub fn set_usage(&mut self, new_usage: GLenum) {
         trace!("Mesh :: Setting usage");
@@ -199,7 +237,7 @@ impl<'a, U, I> Bindable for Mesh<'a, U, I> {
         self.vbo.bind();
         self.ebo.bind();
         self.shader.bind();
-        for t in self.textures {
+        for t in &self.textures {
             t.bind();
         }
     }
@@ -210,24 +248,13 @@ impl<'a, U, I> Bindable for Mesh<'a, U, I> {
         self.vbo.unbind();
         self.ebo.unbind();
         self.shader.unbind();
-        for t in self.textures {
+        for t in &self.textures {
             t.unbind();
         }
     }
-
-    fn delete(&self) {
-        trace!("Mesh :: Deleting");
-        self.vao.delete();
-        self.vbo.delete();
-        self.ebo.delete();
-        self.shader.delete();
-        for t in self.textures {
-            t.delete();
-        }
-    }
 }
 
-impl<'a, U: Debug + Copy, I> Drawable for Mesh<'a, U, I> {
+impl<'a, U: Debug + Copy, I: Index> Drawable for Mesh<'a, U, I> {
     fn draw(&self) {
         trace!(
             "Mesh :: Sending draw call, model {:?}, view {:?}, projection {:?}",
@@ -235,6 +262,9 @@ impl<'a, U: Debug + Copy, I> Drawable for Mesh<'a, U, I> {
             self.view_mat,
             self.projection_mat
         );
+        if let Some(state) = &self.render_state {
+            state.apply();
+        }
         self.bind();
 
         // Uniform the MVP matrices
@@ -244,18 +274,12 @@ impl<'a, U: Debug + Copy, I> Drawable for Mesh<'a, U, I> {
             .uniform_mat4f("u_projection", self.projection_mat);
 
         unsafe {
-            gl::DrawElements(
+            gl_call!(DrawElements(
                 self.primitive,
-                self._primitive_num,
-                gl::UNSIGNED_INT,
+                self.ebo.get_len() as i32,
+                I::DATA_TYPE as GLenum,
                 0 as *const c_void,
-            );
+            ));
         }
     }
 }
-
-impl<'a, U, I> Drop for Mesh<'a, U, I> {
-    fn drop(&mut self) {
-        self.delete();
-    }
-}
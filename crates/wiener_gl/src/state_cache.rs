@@ -0,0 +1,480 @@
+use crate::{Capability, RenderState};
+
+use gl;
+use gl::types::*;
+use log;
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+/// Bindings and state last set through the cache. Anything missing is
+/// unknown, so the next call is always issued.
+#[derive(Default)]
+struct CachedState {
+    program: Option<u32>,
+    vertex_array: Option<u32>,
+    buffers: HashMap<GLenum, u32>,
+
+    /// Element buffers, keyed by the vertex array they are bound to, since
+    /// the element buffer binding is part of the vertex array state.
+    element_buffers: HashMap<u32, u32>,
+    active_texture: Option<u32>,
+    textures: HashMap<(u32, GLenum), u32>,
+    draw_framebuffer: Option<u32>,
+    read_framebuffer: Option<u32>,
+    renderbuffer: Option<u32>,
+    render_state: Option<RenderState>,
+}
+
+thread_local! {
+    static CACHE: RefCell<CachedState> = RefCell::new(CachedState::default());
+}
+
+fn set_capability(capability: Capability, enabled: bool) {
+    unsafe {
+        if enabled {
+            gl_call!(Enable(capability as GLenum));
+        } else {
+            gl_call!(Disable(capability as GLenum));
+        }
+    }
+}
+
+/// Cache of the OpenGL state of the current context, which skips the calls
+/// that would not change anything.
+///
+/// The cache is kept per thread, and is invalidated whenever a `GLWindow`
+/// makes its context current. Code that changes the state by calling OpenGL
+/// directly should call `StateCache::invalidate` afterwards.
+pub struct StateCache;
+
+impl StateCache {
+    /// Forget every cached binding and state.
+    pub fn invalidate() {
+        log::debug!("StateCache :: Invalidating");
+        CACHE.with(|c| *c.borrow_mut() = CachedState::default());
+    }
+
+    /// Forget the last applied `RenderState`.
+    pub fn invalidate_render_state() {
+        CACHE.with(|c| c.borrow_mut().render_state = None);
+    }
+
+    /// Use a shader program.
+    pub fn use_program(id: u32) {
+        CACHE.with(|c| {
+            let mut cache = c.borrow_mut();
+            if cache.program != Some(id) {
+                unsafe {
+                    gl_call!(UseProgram(id));
+                }
+                cache.program = Some(id);
+            }
+        });
+    }
+
+    /// Bind a vertex array.
+    pub fn bind_vertex_array(id: u32) {
+        CACHE.with(|c| {
+            let mut cache = c.borrow_mut();
+            if cache.vertex_array != Some(id) {
+                unsafe {
+                    gl_call!(BindVertexArray(id));
+                }
+                cache.vertex_array = Some(id);
+            }
+        });
+    }
+
+    /// Bind a buffer to the given target.
+    pub fn bind_buffer(target: GLenum, id: u32) {
+        CACHE.with(|c| {
+            let mut cache = c.borrow_mut();
+            if target == gl::ELEMENT_ARRAY_BUFFER {
+                let vao = cache.vertex_array;
+                if vao.is_some_and(|v| cache.element_buffers.get(&v) == Some(&id)) {
+                    return;
+                }
+                unsafe {
+                    gl_call!(BindBuffer(target, id));
+                }
+                if let Some(vao) = vao {
+                    cache.element_buffers.insert(vao, id);
+                }
+            } else if cache.buffers.get(&target) != Some(&id) {
+                unsafe {
+                    gl_call!(BindBuffer(target, id));
+                }
+                cache.buffers.insert(target, id);
+            }
+        });
+    }
+
+    /// Bind a buffer to an indexed target. This also binds it to the generic
+    /// target.
+    pub fn bind_buffer_base(target: GLenum, index: u32, id: u32) {
+        unsafe {
+            gl_call!(BindBufferBase(target, index, id));
+        }
+        CACHE.with(|c| {
+            c.borrow_mut().buffers.insert(target, id);
+        });
+    }
+
+    /// Select the active texture unit.
+    pub fn active_texture(unit: u32) {
+        CACHE.with(|c| {
+            let mut cache = c.borrow_mut();
+            if cache.active_texture != Some(unit) {
+                unsafe {
+                    gl_call!(ActiveTexture(gl::TEXTURE0 + unit));
+                }
+                cache.active_texture = Some(unit);
+            }
+        });
+    }
+
+    /// Bind a texture to the given target of the active texture unit.
+    pub fn bind_texture(target: GLenum, id: u32) {
+        CACHE.with(|c| {
+            let mut cache = c.borrow_mut();
+            match cache.active_texture {
+                Some(unit) => {
+                    if cache.textures.get(&(unit, target)) != Some(&id) {
+                        unsafe {
+                            gl_call!(BindTexture(target, id));
+                        }
+                        cache.textures.insert((unit, target), id);
+                    }
+                }
+                None => unsafe {
+                    gl_call!(BindTexture(target, id));
+                },
+            }
+        });
+    }
+
+    /// Bind a framebuffer to the given target.
+    pub fn bind_framebuffer(target: GLenum, id: u32) {
+        CACHE.with(|c| {
+            let mut cache = c.borrow_mut();
+            let (draw, read) = match target {
+                gl::DRAW_FRAMEBUFFER => (true, false),
+                gl::READ_FRAMEBUFFER => (false, true),
+                _ => (true, true),
+            };
+            if (draw && cache.draw_framebuffer != Some(id))
+                || (read && cache.read_framebuffer != Some(id))
+            {
+                unsafe {
+                    gl_call!(BindFramebuffer(target, id));
+                }
+                if draw {
+                    cache.draw_framebuffer = Some(id);
+                }
+                if read {
+                    cache.read_framebuffer = Some(id);
+                }
+            }
+        });
+    }
+
+    /// Bind a renderbuffer.
+    pub fn bind_renderbuffer(id: u32) {
+        CACHE.with(|c| {
+            let mut cache = c.borrow_mut();
+            if cache.renderbuffer != Some(id) {
+                unsafe {
+                    gl_call!(BindRenderbuffer(gl::RENDERBUFFER, id));
+                }
+                cache.renderbuffer = Some(id);
+            }
+        });
+    }
+
+    /// Forget a shader program that is about to be deleted.
+    pub fn forget_program(id: u32) {
+        CACHE.with(|c| {
+            let mut cache = c.borrow_mut();
+            if cache.program == Some(id) {
+                cache.program = None;
+            }
+        });
+    }
+
+    /// Forget a vertex array that is about to be deleted.
+    pub fn forget_vertex_array(id: u32) {
+        CACHE.with(|c| {
+            let mut cache = c.borrow_mut();
+            if cache.vertex_array == Some(id) {
+                cache.vertex_array = None;
+            }
+            cache.element_buffers.remove(&id);
+        });
+    }
+
+    /// Forget a buffer that is about to be deleted.
+    pub fn forget_buffer(id: u32) {
+        CACHE.with(|c| {
+            let mut cache = c.borrow_mut();
+            cache.buffers.retain(|_, b| *b != id);
+            cache.element_buffers.retain(|_, b| *b != id);
+        });
+    }
+
+    /// Forget a texture that is about to be deleted.
+    pub fn forget_texture(id: u32) {
+        CACHE.with(|c| c.borrow_mut().textures.retain(|_, t| *t != id));
+    }
+
+    /// Forget a framebuffer that is about to be deleted.
+    pub fn forget_framebuffer(id: u32) {
+        CACHE.with(|c| {
+            let mut cache = c.borrow_mut();
+            if cache.draw_framebuffer == Some(id) {
+                cache.draw_framebuffer = None;
+            }
+            if cache.read_framebuffer == Some(id) {
+                cache.read_framebuffer = None;
+            }
+        });
+    }
+
+    /// Forget a renderbuffer that is about to be deleted.
+    pub fn forget_renderbuffer(id: u32) {
+        CACHE.with(|c| {
+            let mut cache = c.borrow_mut();
+            if cache.renderbuffer == Some(id) {
+                cache.renderbuffer = None;
+            }
+        });
+    }
+
+    /// Apply a render state, only issuing the calls for the parts that
+    /// changed since the last applied one.
+    pub fn apply_render_state(state: &RenderState) {
+        CACHE.with(|c| {
+            let mut cache = c.borrow_mut();
+            let previous = cache.render_state;
+            let changed = |f: &dyn Fn(&RenderState) -> bool| {
+                return !previous.is_some_and(|p| f(&p));
+            };
+
+            if changed(&|p| p.depth_test == state.depth_test) {
+                set_capability(Capability::DepthTest, state.depth_test);
+            }
+            if changed(&|p| p.depth_func == state.depth_func) {
+                unsafe {
+                    gl_call!(DepthFunc(state.depth_func as GLenum));
+                }
+            }
+            if changed(&|p| p.depth_write == state.depth_write) {
+                unsafe {
+                    gl_call!(DepthMask(state.depth_write as GLboolean));
+                }
+            }
+
+            if changed(&|p| p.blend.is_some() == state.blend.is_some()) {
+                set_capability(Capability::Blend, state.blend.is_some());
+            }
+            if let Some((src, dst)) = state.blend {
+                if changed(&|p| p.blend == state.blend) {
+                    unsafe {
+                        gl_call!(BlendFunc(src as GLenum, dst as GLenum));
+                    }
+                }
+            }
+
+            if changed(&|p| p.cull_face.is_some() == state.cull_face.is_some()) {
+                set_capability(Capability::CullFace, state.cull_face.is_some());
+            }
+            if let Some(face) = state.cull_face {
+                if changed(&|p| p.cull_face == state.cull_face) {
+                    unsafe {
+                        gl_call!(CullFace(face as GLenum));
+                    }
+                }
+            }
+
+            if changed(&|p| p.polygon_mode == state.polygon_mode) {
+                unsafe {
+                    gl_call!(PolygonMode(
+                        gl::FRONT_AND_BACK,
+                        state.polygon_mode as GLenum
+                    ));
+                }
+            }
+
+            if changed(&|p| p.scissor.is_some() == state.scissor.is_some()) {
+                set_capability(Capability::ScissorTest, state.scissor.is_some());
+            }
+            if let Some((x, y, width, height)) = state.scissor {
+                if changed(&|p| p.scissor == state.scissor) {
+                    unsafe {
+                        gl_call!(Scissor(x, y, width, height));
+                    }
+                }
+            }
+
+            if changed(&|p| p.stencil.is_some() == state.stencil.is_some()) {
+                set_capability(Capability::StencilTest, state.stencil.is_some());
+            }
+            if let Some(stencil) = state.stencil {
+                if changed(&|p| p.stencil == state.stencil) {
+                    unsafe {
+                        gl_call!(StencilFunc(
+                            stencil.func as GLenum,
+                            stencil.reference,
+                            stencil.read_mask,
+                        ));
+                        gl_call!(StencilMask(stencil.write_mask));
+                        gl_call!(StencilOp(
+                            stencil.stencil_fail as GLenum,
+                            stencil.depth_fail as GLenum,
+                            stencil.pass as GLenum,
+                        ));
+                    }
+                }
+            }
+
+            if changed(&|p| p.color_mask == state.color_mask) {
+                let [red, green, blue, alpha] = state.color_mask;
+                unsafe {
+                    gl_call!(ColorMask(
+                        red as GLboolean,
+                        green as GLboolean,
+                        blue as GLboolean,
+                        alpha as GLboolean,
+                    ));
+                }
+            }
+
+            cache.render_state = Some(*state);
+        });
+    }
+}
+
+#[cfg(all(test, feature = "gl-mock"))]
+mod tests {
+    use super::*;
+    use crate::mock::take_call_strings;
+    use crate::{
+        Bindable, BlendEquation, CompareFunc, ElementBuffer, FrontFace, GLManager, HasID, MockGL,
+        ShaderProgram, VertexArray,
+    };
+
+    #[test]
+    fn redundant_binds_are_skipped() {
+        let context = MockGL::load();
+        let program = ShaderProgram::new(&context);
+        let vao = VertexArray::new(&context);
+        MockGL::take_calls();
+
+        program.bind();
+        vao.bind();
+        program.bind();
+        vao.bind();
+        assert_eq!(
+            take_call_strings(),
+            vec![
+                format!("UseProgram({})", program.get_id()),
+                format!("BindVertexArray({})", vao.get_id()),
+            ]
+        );
+    }
+
+    #[test]
+    fn invalidate_issues_the_next_bind() {
+        let context = MockGL::load();
+        let program = ShaderProgram::new(&context);
+        program.bind();
+        MockGL::take_calls();
+
+        StateCache::invalidate();
+        program.bind();
+        assert_eq!(
+            take_call_strings(),
+            vec![format!("UseProgram({})", program.get_id())]
+        );
+    }
+
+    #[test]
+    fn element_buffers_are_cached_per_vertex_array() {
+        let context = MockGL::load();
+        let first = VertexArray::new(&context);
+        let second = VertexArray::new(&context);
+        let ebo = ElementBuffer::<u32>::new(&context);
+
+        first.bind();
+        ebo.bind();
+        second.bind();
+        MockGL::take_calls();
+
+        ebo.bind();
+        assert_eq!(
+            take_call_strings(),
+            vec![format!("BindBuffer(34963, {})", ebo.get_id())]
+        );
+
+        first.bind();
+        MockGL::take_calls();
+        ebo.bind();
+        assert!(take_call_strings().is_empty());
+        assert_eq!(
+            MockGL::get_buffer_binding(gl::ELEMENT_ARRAY_BUFFER),
+            ebo.get_id()
+        );
+    }
+
+    #[test]
+    fn deleted_objects_are_forgotten() {
+        let context = MockGL::load();
+        let vao = VertexArray::new(&context);
+        vao.bind();
+        drop(vao);
+
+        // The new vertex array may reuse the id of the deleted one
+        let vao = VertexArray::new(&context);
+        MockGL::take_calls();
+        vao.bind();
+        assert_eq!(
+            take_call_strings(),
+            vec![format!("BindVertexArray({})", vao.get_id())]
+        );
+    }
+
+    #[test]
+    fn render_state_only_applies_the_changes() {
+        MockGL::load();
+        let state = RenderState::default().depth_test(true);
+        state.apply();
+        assert!(MockGL::is_enabled(Capability::DepthTest));
+        MockGL::take_calls();
+
+        state.apply();
+        assert!(take_call_strings().is_empty());
+
+        state.depth_func(CompareFunc::LessEqual).apply();
+        assert_eq!(take_call_strings(), vec!["DepthFunc(515)"]);
+    }
+
+    #[test]
+    fn state_setters_invalidate_the_render_state() {
+        MockGL::load();
+        let state = RenderState::default();
+        let setters: [fn(); 6] = [
+            || GLManager::front_face(FrontFace::Clockwise),
+            || GLManager::blend_equation(BlendEquation::Add),
+            || GLManager::blend_equation_separate(BlendEquation::Add, BlendEquation::Max),
+            || GLManager::polygon_offset(1.0, 1.0),
+            || GLManager::line_width(2.0),
+            || GLManager::point_size(4.0),
+        ];
+        for setter in setters {
+            state.apply();
+            setter();
+            MockGL::take_calls();
+            state.apply();
+            assert!(!take_call_strings().is_empty());
+        }
+    }
+}
@@ -1,14 +1,18 @@
 use std::ptr::null;
 use gl::types::GLenum;
 
-use crate::{Bindable, HasID, Texture};
+use crate::registry::get_mipmapped_size;
+use crate::{Bindable, GLContext, HasContext, HasID, Labelable, MagFilter, MinFilter, ResourceKind, StateCache, Texture, TextureWrap};
 
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Debug)]
 pub struct CubeMapTexture {
     /// Unique ID associated to the object.
     _id: u32,
 
+    /// Context that created the object.
+    _context: GLContext,
+
     /// Slot to which the image is bound.
     pub tex_num: u32,
 
@@ -22,19 +26,19 @@ pub struct CubeMapTexture {
     pub data_type: GLenum,
 
     /// Method to use for S wrapping.
-    pub wrap_s: GLenum,
+    pub wrap_s: TextureWrap,
 
     /// Method to use for T wrapping.
-    pub wrap_t: GLenum,
+    pub wrap_t: TextureWrap,
 
     /// Method to use for R wrapping.
-    pub wrap_r: GLenum,
+    pub wrap_r: TextureWrap,
 
     /// Method for min filter.
-    pub min_filter: GLenum,
+    pub min_filter: MinFilter,
 
     /// Method for mag filter.
-    pub mag_filter: GLenum,
+    pub mag_filter: MagFilter,
 }
 
 impl Texture for CubeMapTexture {}
@@ -45,7 +49,44 @@ impl HasID for CubeMapTexture {
     }
 }
 
+impl HasContext for CubeMapTexture {
+    fn get_context(&self) -> &GLContext {
+        return &self._context;
+    }
+}
+
+impl Labelable for CubeMapTexture {
+    fn get_resource_kind(&self) -> ResourceKind {
+        return ResourceKind::Texture;
+    }
+}
+
 impl CubeMapTexture {
+    /// Generate a builder for a texture in the given context.
+    #[track_caller]
+    pub fn new(context: &GLContext) -> Self {
+        context.debug_assert_current("CubeMapTexture");
+        let mut tex_id = 0;
+        unsafe {
+            gl_call!(GenTextures(1, &mut tex_id));
+        }
+        log::info!("CubeMapTexture :: Creating new CubeMapTexture {:?}", tex_id);
+        context.register_resource(ResourceKind::Texture, tex_id);
+        return CubeMapTexture {
+            _id: tex_id,
+            _context: context.clone(),
+            tex_num: 0,
+            internal_format: gl::RGB,
+            format: gl::RGB,
+            data_type: gl::UNSIGNED_BYTE,
+            wrap_s: TextureWrap::Repeat,
+            wrap_t: TextureWrap::Repeat,
+            wrap_r: TextureWrap::Repeat,
+            min_filter: MinFilter::Linear,
+            mag_filter: MagFilter::Linear,
+        };
+    }
+
     /// Change the slot of the texture.
     pub fn tex_num(mut self, new_bind: u32) -> Self {
         log::trace!("CubeMapTexture :: Setting texture num {:?}", new_bind);
@@ -75,35 +116,35 @@ impl CubeMapTexture {
     }
 
     /// Change the S wrapping method.
-    pub fn wrap_s(mut self, new_wrap: GLenum) -> Self {
+    pub fn wrap_s(mut self, new_wrap: TextureWrap) -> Self {
         log::trace!("CubeMapTexture :: Setting wrap S {:?}", new_wrap);
         self.wrap_s = new_wrap;
         return self;
     }
 
     /// Change the T wrapping method.
-    pub fn wrap_t(mut self, new_wrap: GLenum) -> Self {
+    pub fn wrap_t(mut self, new_wrap: TextureWrap) -> Self {
         log::trace!("CubeMapTexture :: Setting wrap T {:?}", new_wrap);
         self.wrap_t = new_wrap;
         return self;
     }
 
     /// Change the R wrapping method.
-    pub fn wrap_r(mut self, new_wrap: GLenum) -> Self {
+    pub fn wrap_r(mut self, new_wrap: TextureWrap) -> Self {
         log::trace!("CubeMapTexture :: Setting wrap R {:?}", new_wrap);
         self.wrap_r = new_wrap;
         return self;
     }
 
     /// Change the min filtering method.
-    pub fn min_filter(mut self, new_filter: GLenum) -> Self {
+    pub fn min_filter(mut self, new_filter: MinFilter) -> Self {
         log::trace!("CubeMapTexture :: Setting min filter {:?}", new_filter);
         self.min_filter = new_filter;
         return self;
     }
 
     /// Change the max filtering method.
-    pub fn mag_filter(mut self, new_filter: GLenum) -> Self {
+    pub fn mag_filter(mut self, new_filter: MagFilter) -> Self {
         log::trace!("CubeMapTexture :: Setting mag filter {:?}", new_filter);
         self.mag_filter = new_filter;
         return self;
@@ -115,19 +156,19 @@ impl CubeMapTexture {
         log::info!("CubeMapTexture :: Building CubeMapTexture with parameters:\nWrap S {:?}\nWrap T {:?}\nWrap R {:?}\nMin filter {:?}\nMag filter {:?}", self.wrap_s, self.wrap_t, self.wrap_r, self.min_filter, self.mag_filter);
         self.bind();
         unsafe {
-            gl::TexParameteri(gl::TEXTURE_CUBE_MAP, gl::TEXTURE_WRAP_S, self.wrap_s as i32);
-            gl::TexParameteri(gl::TEXTURE_CUBE_MAP, gl::TEXTURE_WRAP_T, self.wrap_t as i32);
-            gl::TexParameteri(gl::TEXTURE_CUBE_MAP, gl::TEXTURE_WRAP_R, self.wrap_r as i32);
-            gl::TexParameteri(
+            gl_call!(TexParameteri(gl::TEXTURE_CUBE_MAP, gl::TEXTURE_WRAP_S, self.wrap_s as i32));
+            gl_call!(TexParameteri(gl::TEXTURE_CUBE_MAP, gl::TEXTURE_WRAP_T, self.wrap_t as i32));
+            gl_call!(TexParameteri(gl::TEXTURE_CUBE_MAP, gl::TEXTURE_WRAP_R, self.wrap_r as i32));
+            gl_call!(TexParameteri(
                 gl::TEXTURE_CUBE_MAP,
                 gl::TEXTURE_MIN_FILTER,
                 self.min_filter as i32,
-            );
-            gl::TexParameteri(
+            ));
+            gl_call!(TexParameteri(
                 gl::TEXTURE_CUBE_MAP,
                 gl::TEXTURE_MAG_FILTER,
                 self.mag_filter as i32,
-            );
+            ));
         }
         self.unbind();
         return self;
@@ -139,7 +180,7 @@ impl CubeMapTexture {
         self.bind();
         unsafe {
             for i in 0..6_usize {
-                gl::TexImage2D(
+                gl_call!(TexImage2D(
                     gl::TEXTURE_CUBE_MAP_POSITIVE_X + i as u32,
                     0,
                     self.internal_format as i32,
@@ -149,11 +190,12 @@ impl CubeMapTexture {
                     self.format,
                     self.data_type,
                     data[i].as_ptr() as *const _,
-                );
-                gl::GenerateMipmap(gl::TEXTURE_CUBE_MAP);
+                ));
+                gl_call!(GenerateMipmap(gl::TEXTURE_CUBE_MAP));
             }
         }
         self.unbind();
+        self.set_size(widths, heights);
     }
 
     /// Allocate memory for the texture without buffering anything.
@@ -162,7 +204,7 @@ impl CubeMapTexture {
         self.bind();
         unsafe {
             for i in 0..6_usize {
-                gl::TexImage2D(
+                gl_call!(TexImage2D(
                     gl::TEXTURE_CUBE_MAP_POSITIVE_X + i as u32,
                     0,
                     self.internal_format as i32,
@@ -172,78 +214,68 @@ impl CubeMapTexture {
                     self.format,
                     self.data_type,
                     null(),
-                );
-                gl::GenerateMipmap(gl::TEXTURE_CUBE_MAP);
+                ));
+                gl_call!(GenerateMipmap(gl::TEXTURE_CUBE_MAP));
             }
         }
         self.unbind();
+        self.set_size(widths, heights);
+    }
+
+    /// Record the estimated size of the six faces in the registry.
+    fn set_size(&self, widths: [i32; 6], heights: [i32; 6]) {
+        let size = (0..6_usize)
+            .map(|i| get_mipmapped_size(widths[i], heights[i], self.internal_format))
+            .sum();
+        self._context.set_resource_size(ResourceKind::Texture, self.get_id(), size);
     }
 
     /// Bind the slot associated to the texture.
     pub fn bind_slot(&self) {
         log::trace!("CubeMapTexture :: Binding texture slot");
-        unsafe {
-            gl::ActiveTexture(gl::TEXTURE0 + self.tex_num);
-        }
-    }
-}
-
-impl Default for CubeMapTexture {
-    fn default() -> Self {
-        let mut tex_id = 0;
-        unsafe {
-            gl::GenTextures(1, &mut tex_id);
-        }
-        log::info!("CubeMapTexture :: Creating new CubeMapTexture {:?}", tex_id);
-        return CubeMapTexture {
-            _id: tex_id,
-            tex_num: 0,
-            internal_format: gl::RGB,
-            format: gl::RGB,
-            data_type: gl::UNSIGNED_BYTE,
-            wrap_s: gl::REPEAT,
-            wrap_t: gl::REPEAT,
-            wrap_r: gl::REPEAT,
-            min_filter: gl::LINEAR,
-            mag_filter: gl::LINEAR,
-        };
+        StateCache::active_texture(self.tex_num);
     }
 }
 
 impl Bindable for CubeMapTexture {
     fn bind(&self) {
+        self._context.debug_assert_current("CubeMapTexture");
         log::trace!(
             "CubeMapTexture :: Binding texture {:?} to slot {:?}",
             self.get_id(),
             self.tex_num
         );
         self.bind_slot();
-        unsafe {
-            gl::BindTexture(gl::TEXTURE_CUBE_MAP, self.get_id());
-        }
+        StateCache::bind_texture(gl::TEXTURE_CUBE_MAP, self.get_id());
     }
 
     fn unbind(&self) {
+        self._context.debug_assert_current("CubeMapTexture");
         log::trace!(
             "CubeMapTexture :: Unbinding texture {:?} to slot {:?}",
             self.get_id(),
             self.tex_num
         );
         self.bind_slot();
-        unsafe {
-            gl::BindTexture(gl::TEXTURE_CUBE_MAP, 0);
-        }
+        StateCache::bind_texture(gl::TEXTURE_CUBE_MAP, 0);
     }
+}
 
-    fn delete(&self) {
+impl Drop for CubeMapTexture {
+    fn drop(&mut self) {
+        if !self._context.check_delete("CubeMapTexture") {
+            return;
+        }
         log::trace!(
             "CubeMapTexture :: Deleting texture {:?} bound to slot {:?}",
             self.get_id(),
             self.tex_num
         );
-        self.bind();
+        StateCache::forget_texture(self.get_id());
         unsafe {
-            gl::DeleteTextures(1, &self.get_id());
+            gl_call!(DeleteTextures(1, &self.get_id()));
         }
+        self._context
+            .unregister_resource(ResourceKind::Texture, self.get_id());
     }
 }
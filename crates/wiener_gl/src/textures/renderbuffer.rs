@@ -1,13 +1,16 @@
-use crate::{Bindable, HasID, Texture};
+use crate::capabilities::clamp_samples_to_current;
+use crate::registry::get_pixel_size;
+use crate::{Bindable, GLContext, HasContext, HasID, Labelable, ResourceKind, StateCache, Texture};
 
 use gl;
 use gl::types::*;
 
 /// OpenGL render buffer, which can be drawn to efficiently, but is not
 /// intended for reading.
-#[derive(Clone, Debug)]
+#[derive(Debug)]
 pub struct RenderBuffer {
     _id: u32,
+    _context: GLContext,
 }
 
 impl Texture for RenderBuffer {}
@@ -18,15 +21,33 @@ impl HasID for RenderBuffer {
     }
 }
 
+impl HasContext for RenderBuffer {
+    fn get_context(&self) -> &GLContext {
+        return &self._context;
+    }
+}
+
+impl Labelable for RenderBuffer {
+    fn get_resource_kind(&self) -> ResourceKind {
+        return ResourceKind::RenderBuffer;
+    }
+}
+
 impl RenderBuffer {
-    /// Create a new render buffer.
-    pub fn new() -> Self {
+    /// Create a new render buffer in the given context.
+    #[track_caller]
+    pub fn new(context: &GLContext) -> Self {
+        context.debug_assert_current("RenderBuffer");
         let mut rbo = 0;
         unsafe {
-            gl::GenRenderbuffers(1, &mut rbo);
+            gl_call!(GenRenderbuffers(1, &mut rbo));
         }
         log::info!("RenderBuffer :: Creating renderbuffer {:?}", rbo);
-        return RenderBuffer { _id: rbo };
+        context.register_resource(ResourceKind::RenderBuffer, rbo);
+        return RenderBuffer {
+            _id: rbo,
+            _context: context.clone(),
+        };
     }
 
     /// Set up the render buffer, giving it a format, width and height.
@@ -34,49 +55,68 @@ impl RenderBuffer {
         log::debug!("RenderBuffer :: Setting up render buffer");
         self.bind();
         unsafe {
-            gl::RenderbufferStorage(gl::RENDERBUFFER, format, width, height);
+            gl_call!(RenderbufferStorage(gl::RENDERBUFFER, format, width, height));
         }
         self.unbind();
+        self.set_size(format, width * height);
         return self;
     }
 
-    /// Set up a multisampled renderbuffer.
+    /// Set up a multisampled renderbuffer. The number of samples is clamped
+    /// to the maximum supported by the current `GLCapabilities`.
     pub fn set_up_multisample(self, samples: i32, format: GLenum, width: i32, height: i32) -> Self {
         log::debug!("RenderBuffer :: Setting up multisampled render buffer");
+        let samples = clamp_samples_to_current(samples);
         self.bind();
         unsafe {
-            gl::RenderbufferStorageMultisample(gl::RENDERBUFFER, samples, format, width, height);
+            gl_call!(RenderbufferStorageMultisample(
+                gl::RENDERBUFFER,
+                samples,
+                format,
+                width,
+                height
+            ));
         }
         self.unbind();
+        self.set_size(format, width * height * samples.max(1));
         return self;
     }
+
+    /// Record the estimated size of the storage in the registry.
+    fn set_size(&self, format: GLenum, pixels: i32) {
+        self._context.set_resource_size(
+            ResourceKind::RenderBuffer,
+            self.get_id(),
+            pixels.max(0) as usize * get_pixel_size(format),
+        );
+    }
 }
 
 impl Bindable for RenderBuffer {
     fn bind(&self) {
+        self._context.debug_assert_current("RenderBuffer");
         log::trace!("RenderBuffer :: Binding");
-        unsafe {
-            gl::BindRenderbuffer(gl::RENDERBUFFER, self.get_id());
-        }
+        StateCache::bind_renderbuffer(self.get_id());
     }
 
     fn unbind(&self) {
+        self._context.debug_assert_current("RenderBuffer");
         log::trace!("RenderBuffer :: Unbinding");
-        unsafe {
-            gl::BindRenderbuffer(gl::RENDERBUFFER, 0);
-        }
-    }
-
-    fn delete(&self) {
-        log::trace!("RenderBuffer :: Deleting");
-        unsafe {
-            gl::DeleteRenderbuffers(1, &self.get_id());
-        }
+        StateCache::bind_renderbuffer(0);
     }
 }
 
 impl Drop for RenderBuffer {
     fn drop(&mut self) {
-        self.delete();
+        if !self._context.check_delete("RenderBuffer") {
+            return;
+        }
+        log::trace!("RenderBuffer :: Deleting");
+        StateCache::forget_renderbuffer(self.get_id());
+        unsafe {
+            gl_call!(DeleteRenderbuffers(1, &self.get_id()));
+        }
+        self._context
+            .unregister_resource(ResourceKind::RenderBuffer, self.get_id());
     }
 }
@@ -1,7 +1,12 @@
 use std::ffi::c_void;
 use std::ptr::null;
 
-use crate::{Bindable, HasID, Texture};
+use crate::capabilities::clamp_samples_to_current;
+use crate::registry::{get_mipmapped_size, get_pixel_size};
+use crate::{
+    Bindable, Fence, GLContext, HasContext, HasID, Labelable, MagFilter, MinFilter, ResourceKind,
+    StateCache, Texture, TextureWrap,
+};
 
 use gl;
 use gl::types::*;
@@ -9,11 +14,14 @@ use log;
 use wiener_utils::image;
 
 /// 2D texture.
-#[derive(Clone, Copy, Debug)]
+#[derive(Debug)]
 pub struct Texture2D {
     /// Unique ID associated to the object.
     _id: u32,
 
+    /// Context that created the object.
+    _context: GLContext,
+
     /// Slot to which the image is bound.
     pub tex_num: u32,
 
@@ -27,19 +35,19 @@ pub struct Texture2D {
     pub data_type: GLenum,
 
     /// Method to use for S wrapping.
-    pub wrap_s: GLenum,
+    pub wrap_s: TextureWrap,
 
     /// Method to use for T wrapping.
-    pub wrap_t: GLenum,
+    pub wrap_t: TextureWrap,
 
     /// Method to use for R wrapping.
-    pub wrap_r: GLenum,
+    pub wrap_r: TextureWrap,
 
     /// Method for min filter.
-    pub min_filter: GLenum,
+    pub min_filter: MinFilter,
 
     /// Method for mag filter.
-    pub mag_filter: GLenum,
+    pub mag_filter: MagFilter,
 }
 
 impl Texture for Texture2D {}
@@ -50,7 +58,44 @@ impl HasID for Texture2D {
     }
 }
 
+impl HasContext for Texture2D {
+    fn get_context(&self) -> &GLContext {
+        return &self._context;
+    }
+}
+
+impl Labelable for Texture2D {
+    fn get_resource_kind(&self) -> ResourceKind {
+        return ResourceKind::Texture;
+    }
+}
+
 impl Texture2D {
+    /// Generate a builder for a texture in the given context.
+    #[track_caller]
+    pub fn new(context: &GLContext) -> Self {
+        context.debug_assert_current("Texture2D");
+        let mut tex_id = 0;
+        unsafe {
+            gl_call!(GenTextures(1, &mut tex_id));
+        }
+        log::info!("Texture2D :: Creating new Texture2D {:?}", tex_id);
+        context.register_resource(ResourceKind::Texture, tex_id);
+        return Texture2D {
+            _id: tex_id,
+            _context: context.clone(),
+            tex_num: 0,
+            internal_format: gl::RGB,
+            format: gl::RGB,
+            data_type: gl::UNSIGNED_BYTE,
+            wrap_s: TextureWrap::Repeat,
+            wrap_t: TextureWrap::Repeat,
+            wrap_r: TextureWrap::Repeat,
+            min_filter: MinFilter::Linear,
+            mag_filter: MagFilter::Linear,
+        };
+    }
+
     /// Change the slot of the texture.
     pub fn tex_num(mut self, new_bind: u32) -> Self {
         log::trace!("Texture2D :: Setting texture num {:?}", new_bind);
@@ -80,35 +125,35 @@ impl Texture2D {
     }
 
     /// Change the S wrapping method.
-    pub fn wrap_s(mut self, new_wrap: GLenum) -> Self {
+    pub fn wrap_s(mut self, new_wrap: TextureWrap) -> Self {
         log::trace!("Texture2D :: Setting wrap S {:?}", new_wrap);
         self.wrap_s = new_wrap;
         return self;
     }
 
     /// Change the T wrapping method.
-    pub fn wrap_t(mut self, new_wrap: GLenum) -> Self {
+    pub fn wrap_t(mut self, new_wrap: TextureWrap) -> Self {
         log::trace!("Texture2D :: Setting wrap T {:?}", new_wrap);
         self.wrap_t = new_wrap;
         return self;
     }
 
     /// Change the R wrapping method.
-    pub fn wrap_r(mut self, new_wrap: GLenum) -> Self {
+    pub fn wrap_r(mut self, new_wrap: TextureWrap) -> Self {
         log::trace!("Texture2D :: Setting wrap R {:?}", new_wrap);
         self.wrap_r = new_wrap;
         return self;
     }
 
     /// Change the min filtering method.
-    pub fn min_filter(mut self, new_filter: GLenum) -> Self {
+    pub fn min_filter(mut self, new_filter: MinFilter) -> Self {
         log::trace!("Texture2D :: Setting min filter {:?}", new_filter);
         self.min_filter = new_filter;
         return self;
     }
 
     /// Change the max filtering method.
-    pub fn mag_filter(mut self, new_filter: GLenum) -> Self {
+    pub fn mag_filter(mut self, new_filter: MagFilter) -> Self {
         log::trace!("Texture2D :: Setting mag filter {:?}", new_filter);
         self.mag_filter = new_filter;
         return self;
@@ -120,19 +165,31 @@ impl Texture2D {
         log::info!("Texture2D :: Building Texture2D with parameters:\nWrap S {:?}\nWrap T {:?}\nWrap R {:?}\nMin filter {:?}\nMag filter {:?}", self.wrap_s, self.wrap_t, self.wrap_r, self.min_filter, self.mag_filter);
         self.bind();
         unsafe {
-            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, self.wrap_s as i32);
-            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, self.wrap_t as i32);
-            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_R, self.wrap_r as i32);
-            gl::TexParameteri(
+            gl_call!(TexParameteri(
+                gl::TEXTURE_2D,
+                gl::TEXTURE_WRAP_S,
+                self.wrap_s as i32
+            ));
+            gl_call!(TexParameteri(
+                gl::TEXTURE_2D,
+                gl::TEXTURE_WRAP_T,
+                self.wrap_t as i32
+            ));
+            gl_call!(TexParameteri(
+                gl::TEXTURE_2D,
+                gl::TEXTURE_WRAP_R,
+                self.wrap_r as i32
+            ));
+            gl_call!(TexParameteri(
                 gl::TEXTURE_2D,
                 gl::TEXTURE_MIN_FILTER,
                 self.min_filter as i32,
-            );
-            gl::TexParameteri(
+            ));
+            gl_call!(TexParameteri(
                 gl::TEXTURE_2D,
                 gl::TEXTURE_MAG_FILTER,
                 self.mag_filter as i32,
-            );
+            ));
         }
         self.unbind();
         return self;
@@ -147,7 +204,7 @@ impl Texture2D {
         );
         self.bind();
         unsafe {
-            gl::TexImage2D(
+            gl_call!(TexImage2D(
                 gl::TEXTURE_2D,
                 0,
                 self.internal_format as i32,
@@ -157,10 +214,19 @@ impl Texture2D {
                 self.format,
                 self.data_type,
                 data.as_ptr() as *const _,
-            );
-            gl::GenerateMipmap(gl::TEXTURE_2D);
+            ));
+            gl_call!(GenerateMipmap(gl::TEXTURE_2D));
         }
         self.unbind();
+        self.set_size(get_mipmapped_size(width, height, self.internal_format));
+    }
+
+    /// Buffer the given image to the texture, returning a fence that is
+    /// signaled once the GPU is done with the upload.
+    #[track_caller]
+    pub fn buffer_img_fenced<T>(&self, data: &[T], width: i32, height: i32) -> Fence {
+        self.buffer_img(data, width, height);
+        return Fence::new(&self._context);
     }
 
     /// Allocate memory for the texture without buffering anything.
@@ -172,7 +238,7 @@ impl Texture2D {
         );
         self.bind();
         unsafe {
-            gl::TexImage2D(
+            gl_call!(TexImage2D(
                 gl::TEXTURE_2D,
                 0,
                 self.internal_format as i32,
@@ -182,31 +248,36 @@ impl Texture2D {
                 self.format,
                 self.data_type,
                 null(),
-            );
-            gl::GenerateMipmap(gl::TEXTURE_2D);
+            ));
+            gl_call!(GenerateMipmap(gl::TEXTURE_2D));
         }
         self.unbind();
+        self.set_size(get_mipmapped_size(width, height, self.internal_format));
     }
 
-    /// Allocate a multisampled empty buffer.
+    /// Allocate a multisampled empty buffer. The number of samples is
+    /// clamped to the maximum supported by the current `GLCapabilities`.
     pub fn buffer_multisampled(&self, samples: i32, width: i32, height: i32) {
         log::info!(
             "Texture2D :: Allocating for a {:?}x{:?} multisampled image",
             width,
             height
         );
+        let samples = clamp_samples_to_current(samples);
         self.bind_multisample();
         unsafe {
-            gl::TexImage2DMultisample(
+            gl_call!(TexImage2DMultisample(
                 gl::TEXTURE_2D_MULTISAMPLE,
                 samples,
                 self.internal_format,
                 width,
                 height,
                 gl::TRUE,
-            );
+            ));
         }
         self.unbind_multisample();
+        let pixels = (width.max(0) * height.max(0) * samples.max(1)) as usize;
+        self.set_size(pixels * get_pixel_size(self.internal_format));
     }
 
     /// Buffer an image contained in a file to the texture.
@@ -251,7 +322,7 @@ impl Texture2D {
         data.resize((width * height) as usize, [0, 0, 0]);
         unsafe {
             log::debug!("Texture2D :: Reading data from texture");
-            gl::ReadPixels(
+            gl_call!(ReadPixels(
                 window.0,
                 window.1,
                 window.2,
@@ -259,7 +330,7 @@ impl Texture2D {
                 self.format,
                 self.data_type,
                 data.as_mut_ptr() as *mut c_void,
-            );
+            ));
         }
         log::debug!("Texture2D :: Setting data up to save it");
         data.reverse();
@@ -281,7 +352,7 @@ impl Texture2D {
         data.resize((width * height) as usize, [0, 0, 0, 0]);
         unsafe {
             log::debug!("Texture2D :: Reading data from texture");
-            gl::ReadPixels(
+            gl_call!(ReadPixels(
                 window.0,
                 window.1,
                 window.2,
@@ -289,7 +360,7 @@ impl Texture2D {
                 self.format,
                 self.data_type,
                 data.as_mut_ptr() as *mut c_void,
-            );
+            ));
         }
         log::debug!("Texture2D :: Setting data up to save it");
         data.reverse();
@@ -312,7 +383,7 @@ impl Texture2D {
         data.resize((width * height) as usize, [0, 0, 0]);
         unsafe {
             log::debug!("Texture2D :: Reading data from texture");
-            gl::ReadPixels(
+            gl_call!(ReadPixels(
                 window.0,
                 window.1,
                 window.2,
@@ -320,7 +391,7 @@ impl Texture2D {
                 self.format,
                 self.data_type,
                 data.as_mut_ptr() as *mut c_void,
-            );
+            ));
         }
         log::debug!("Texture2D :: Setting data up to save it");
         data.reverse();
@@ -342,7 +413,7 @@ impl Texture2D {
         data.resize((width * height) as usize, [0, 0, 0, 0]);
         unsafe {
             log::debug!("Texture2D :: Reading data from texture");
-            gl::ReadPixels(
+            gl_call!(ReadPixels(
                 window.0,
                 window.1,
                 window.2,
@@ -350,7 +421,7 @@ impl Texture2D {
                 self.format,
                 self.data_type,
                 data.as_mut_ptr() as *mut c_void,
-            );
+            ));
         }
         log::debug!("Texture2D :: Setting data up to save it");
         data.reverse();
@@ -373,7 +444,7 @@ impl Texture2D {
         data.resize((width * height) as usize, [0.0, 0.0, 0.0]);
         unsafe {
             log::debug!("Texture2D :: Reading data from texture");
-            gl::ReadPixels(
+            gl_call!(ReadPixels(
                 window.0,
                 window.1,
                 window.2,
@@ -381,7 +452,7 @@ impl Texture2D {
                 self.format,
                 self.data_type,
                 data.as_mut_ptr() as *mut c_void,
-            );
+            ));
         }
         log::debug!("Texture2D :: Setting data up to save it");
         data.reverse();
@@ -403,7 +474,7 @@ impl Texture2D {
         data.resize((width * height) as usize, [0.0, 0.0, 0.0, 0.0]);
         unsafe {
             log::debug!("Texture2D :: Reading data from texture");
-            gl::ReadPixels(
+            gl_call!(ReadPixels(
                 window.0,
                 window.1,
                 window.2,
@@ -411,7 +482,7 @@ impl Texture2D {
                 self.format,
                 self.data_type,
                 data.as_mut_ptr() as *mut c_void,
-            );
+            ));
         }
         log::debug!("Texture2D :: Setting data up to save it");
         data.reverse();
@@ -423,85 +494,68 @@ impl Texture2D {
         image::save_image_rgba_f32(filename, result.as_slice(), width, height);
     }
 
+    /// Record the estimated size of the texture in the registry.
+    fn set_size(&self, size: usize) {
+        self._context
+            .set_resource_size(ResourceKind::Texture, self.get_id(), size);
+    }
+
     /// Bind the slot associated to the texture.
     pub fn bind_slot(&self) {
         log::trace!("Texture2D :: Binding texture slot");
-        unsafe {
-            gl::ActiveTexture(gl::TEXTURE0 + self.tex_num);
-        }
+        StateCache::active_texture(self.tex_num);
     }
 
     pub fn bind_multisample(&self) {
         log::trace!("Texture2D :: Binding texture multisampled");
-        unsafe {
-            gl::BindTexture(gl::TEXTURE_2D_MULTISAMPLE, self.get_id());
-        }
+        StateCache::bind_texture(gl::TEXTURE_2D_MULTISAMPLE, self.get_id());
     }
 
     pub fn unbind_multisample(&self) {
         log::trace!("Texture2D :: Unbinding texture multisampled");
-        unsafe {
-            gl::BindTexture(gl::TEXTURE_2D_MULTISAMPLE, self.get_id());
-        }
-    }
-}
-
-impl Default for Texture2D {
-    fn default() -> Self {
-        let mut tex_id = 0;
-        unsafe {
-            gl::GenTextures(1, &mut tex_id);
-        }
-        log::info!("Texture2D :: Creating new Texture2D {:?}", tex_id);
-        return Texture2D {
-            _id: tex_id,
-            tex_num: 0,
-            internal_format: gl::RGB,
-            format: gl::RGB,
-            data_type: gl::UNSIGNED_BYTE,
-            wrap_s: gl::REPEAT,
-            wrap_t: gl::REPEAT,
-            wrap_r: gl::REPEAT,
-            min_filter: gl::LINEAR,
-            mag_filter: gl::LINEAR,
-        };
+        StateCache::bind_texture(gl::TEXTURE_2D_MULTISAMPLE, 0);
     }
 }
 
 impl Bindable for Texture2D {
     fn bind(&self) {
+        self._context.debug_assert_current("Texture2D");
         log::trace!(
             "Texture2D :: Binding texture {:?} to slot {:?}",
             self.get_id(),
             self.tex_num
         );
         self.bind_slot();
-        unsafe {
-            gl::BindTexture(gl::TEXTURE_2D, self.get_id());
-        }
+        StateCache::bind_texture(gl::TEXTURE_2D, self.get_id());
     }
 
     fn unbind(&self) {
+        self._context.debug_assert_current("Texture2D");
         log::trace!(
             "Texture2D :: Unbinding texture {:?} to slot {:?}",
             self.get_id(),
             self.tex_num
         );
         self.bind_slot();
-        unsafe {
-            gl::BindTexture(gl::TEXTURE_2D, 0);
-        }
+        StateCache::bind_texture(gl::TEXTURE_2D, 0);
     }
+}
 
-    fn delete(&self) {
+impl Drop for Texture2D {
+    fn drop(&mut self) {
+        if !self._context.check_delete("Texture2D") {
+            return;
+        }
         log::trace!(
             "Texture2D :: Deleting texture {:?} bound to slot {:?}",
             self.get_id(),
             self.tex_num
         );
-        self.bind();
+        StateCache::forget_texture(self.get_id());
         unsafe {
-            gl::DeleteTextures(1, &self.get_id());
+            gl_call!(DeleteTextures(1, &self.get_id()));
         }
+        self._context
+            .unregister_resource(ResourceKind::Texture, self.get_id());
     }
 }
@@ -1,12 +1,13 @@
-/// Trait for any object that can be bound, unbound and removed from some
-/// space in GPU memory.
+use crate::{set_object_label, GLContext, ResourceKind};
+
+/// Trait for any object that can be bound and unbound. Objects are deleted
+/// from GPU memory when they are dropped, so share them with `Rc` instead of
+/// copying their handles.
 pub trait Bindable {
     /// Bind the current object.
     fn bind(&self);
     /// Unbind the current object.
     fn unbind(&self);
-    /// Delete the current object from memory.
-    fn delete(&self);
 }
 
 /// Trait for objects that have a unique GPU id.
@@ -15,8 +16,39 @@ pub trait HasID {
     fn get_id(&self) -> u32;
 }
 
+/// Trait for objects that belong to an OpenGL context.
+pub trait HasContext {
+    /// Get the context that created the object.
+    fn get_context(&self) -> &GLContext;
+}
+
 /// Trait of objects that can be drawn onto a frame buffer.
 pub trait Drawable {
     /// Draw this object.
     fn draw(&self);
 }
+
+/// Trait for objects that can be given a name shown by debugging tools.
+pub trait Labelable: HasID + HasContext {
+    /// Get the kind of object, as used by `glObjectLabel`.
+    fn get_resource_kind(&self) -> ResourceKind;
+
+    /// Set the debug name of the object, which is also recorded in the
+    /// registry of its context. Objects generated but never bound do not
+    /// exist yet, so debugging tools will not show their name.
+    fn set_label(&self, label: &str) {
+        let kind = self.get_resource_kind();
+        set_object_label(kind.into(), self.get_id(), label);
+        self.get_context()
+            .set_resource_label(kind, self.get_id(), label);
+    }
+
+    /// Set the debug name of the object, returning `self`.
+    fn label(self, label: &str) -> Self
+    where
+        Self: Sized,
+    {
+        self.set_label(label);
+        return self;
+    }
+}
@@ -1,42 +1,74 @@
+use crate::{enable_debug_output, GLCapabilities, GLContext, StateCache};
+
 use glfw;
 use glfw::Context;
 use log;
+use std::rc::Rc;
 use std::sync::mpsc::Receiver;
-use wiener_core::{init_glfw, WindowDescriptor};
+use std::time::Instant;
+use wiener_core::{
+    get_gamepads, get_monitors, init_glfw, init_headless, init_shared_glfw, set_window_mode,
+    update_gamepad_mappings, Event, GamepadInfo, GamepadPoller, GlfwHandles, HeadlessContext,
+    InputRecording, InputReplay, InputState, MonitorInfo, SwapInterval, WindowDescriptor,
+    WindowError, WindowMode,
+};
+use wiener_utils::image;
 
 /// Window that can render OpenGL stuff.
+///
+/// The window owns its context, which is destroyed along with it, so every
+/// resource created with the context should be dropped before the window.
+/// Resources that outlive it are logged as leaks and are not deleted.
+///
+/// A headless window (see `WindowDescriptor::headless`) has a surfaceless
+/// context created through EGL instead of a GLFW window, so it needs no
+/// display server, and it has no default framebuffer, so everything must be
+/// drawn to a `FrameBuffer`. It can still be driven by `run` and replay
+/// recordings, while the operations on the window itself (mode, title,
+/// icon, cursor, clipboard, monitors and gamepads) do nothing.
 pub struct GLWindow {
     _descriptor: WindowDescriptor,
-    _glfw_window: glfw::Window,
-    _events: Receiver<(f64, glfw::WindowEvent)>,
-    _glfw_instance: glfw::Glfw,
+    _backend: WindowBackend,
+    _events: Vec<Event>,
+    _input: InputState,
+    _gamepad_poller: GamepadPoller,
+    _recording: Option<InputRecording>,
+    _replay: Option<InputReplay>,
+    _virtual_time: Option<f64>,
     _gl_version: (u32, u32),
     _gl_profile: glfw::OpenGlProfileHint,
+    _context: GLContext,
+    _windowed_rect: (i32, i32, i32, i32),
 }
 
-impl GLWindow {
-    /// Generate a builder for the window.
-    pub fn builder() -> Self {
-        log::info!("GLWindow :: Creating new GLWindow");
-        let mut empty_instance = glfw::init(glfw::FAIL_ON_ERRORS).unwrap();
-        let (window, receiver) = empty_instance
-            .create_window(1, 1, "", glfw::WindowMode::Windowed)
-            .expect("");
-        return GLWindow {
-            _descriptor: WindowDescriptor::default(),
-            _glfw_window: window,
-            _events: receiver,
-            _glfw_instance: empty_instance,
-            _gl_version: (4, 6),
-            _gl_profile: glfw::OpenGlProfileHint::Core,
-        };
-    }
+/// System handles behind a `GLWindow`.
+enum WindowBackend {
+    /// Window created by GLFW.
+    Glfw {
+        window: glfw::Window,
+        receiver: Receiver<(f64, glfw::WindowEvent)>,
+        instance: glfw::Glfw,
+    },
 
-    /// Get the GLFW window.
-    pub fn get_window(&self) -> &glfw::Window {
-        return &self._glfw_window;
-    }
+    /// Surfaceless context created through EGL, with no window. Time is
+    /// measured from its creation.
+    Headless {
+        context: Box<HeadlessContext>,
+        start: Instant,
+        should_close: bool,
+    },
+}
+
+/// Builder for a `GLWindow`. Nothing is created until `build` is called.
+#[derive(Clone, Debug)]
+pub struct GLWindowBuilder {
+    _descriptor: WindowDescriptor,
+    _gl_version: (u32, u32),
+    _gl_fallback_versions: Vec<(u32, u32)>,
+    _gl_profile: glfw::OpenGlProfileHint,
+}
 
+impl GLWindowBuilder {
     /// Get the GLFW window descriptor.
     pub fn get_descriptor(&self) -> &WindowDescriptor {
         return &self._descriptor;
@@ -54,57 +86,652 @@ impl GLWindow {
         return self;
     }
 
+    /// Set the OpenGL versions to try, in order, if the main version is not
+    /// available.
+    pub fn fallback_versions(mut self, new_versions: &[(u32, u32)]) -> Self {
+        self._gl_fallback_versions = new_versions.to_vec();
+        return self;
+    }
+
     /// Set the OpenGL profile.
     pub fn profile(mut self, new_profile: glfw::OpenGlProfileHint) -> Self {
         self._gl_profile = new_profile;
         return self;
     }
 
+    /// Set whether to create a debug context whose messages are forwarded to
+    /// `log` or not.
+    pub fn debug(mut self, debug: bool) -> Self {
+        self._descriptor.debug_context = debug;
+        return self;
+    }
+
+    /// Set whether the window is headless or not.
+    pub fn headless(mut self, headless: bool) -> Self {
+        self._descriptor.headless = headless;
+        return self;
+    }
+
+    /// Build the window along with its `GLContext`, trying the main OpenGL
+    /// version first and then each of the fallback versions.
+    ///
+    /// Headless windows are created through `init_headless`, and are always
+    /// made current.
+    pub fn build(self) -> Result<GLWindow, WindowError> {
+        log::info!("GLWindow :: Building window");
+        let mut versions = vec![self._gl_version];
+        versions.extend(&self._gl_fallback_versions);
+        if self._descriptor.headless {
+            return self.build_headless(&versions);
+        }
+        let (mut window, events, glfw_inst) =
+            init_glfw(&self._descriptor, &versions, self._gl_profile)?;
+        init_gl(&mut window);
+        let context = GLContext::new();
+        if window.is_current() {
+            context.set_current();
+            if self._descriptor.debug_context {
+                enable_debug_output(&context);
+            }
+            context.set_capabilities(Rc::new(GLCapabilities::query()));
+        }
+        return Ok(GLWindow::from_handles(
+            (window, events, glfw_inst),
+            self._descriptor,
+            self._gl_profile,
+            context,
+        ));
+    }
+
+    /// Build a headless window through EGL.
+    fn build_headless(self, versions: &[(u32, u32)]) -> Result<GLWindow, WindowError> {
+        let headless = init_headless(versions, self._gl_profile, self._descriptor.debug_context)?;
+        gl::load_with(|s| headless.get_proc_address(s));
+        StateCache::invalidate();
+        let context = GLContext::new();
+        context.set_current();
+        if self._descriptor.debug_context {
+            enable_debug_output(&context);
+        }
+        context.set_capabilities(Rc::new(GLCapabilities::query()));
+        let version = get_context_version();
+        log::info!(
+            "GLWindow :: Created headless context with version {:?}.{:?}",
+            version.0,
+            version.1
+        );
+        let backend = WindowBackend::Headless {
+            context: Box::new(headless),
+            start: Instant::now(),
+            should_close: false,
+        };
+        return Ok(GLWindow::from_backend(
+            backend,
+            self._descriptor,
+            version,
+            self._gl_profile,
+            context,
+        ));
+    }
+}
+
+impl Default for GLWindowBuilder {
+    fn default() -> Self {
+        return GLWindowBuilder {
+            _descriptor: WindowDescriptor::default(),
+            _gl_version: (4, 6),
+            _gl_fallback_versions: Vec::new(),
+            _gl_profile: glfw::OpenGlProfileHint::Core,
+        };
+    }
+}
+
+impl GLWindow {
+    /// Wrap the handles of a created window.
+    fn from_handles(
+        handles: GlfwHandles,
+        descriptor: WindowDescriptor,
+        profile: glfw::OpenGlProfileHint,
+        context: GLContext,
+    ) -> Self {
+        let (window, events, glfw_inst) = handles;
+        let version = window.get_context_version();
+        log::info!(
+            "GLWindow :: Created context with version {:?}.{:?}",
+            version.major,
+            version.minor
+        );
+        if descriptor.make_current {
+            StateCache::invalidate();
+            context.set_current();
+        }
+        let backend = WindowBackend::Glfw {
+            window,
+            receiver: events,
+            instance: glfw_inst,
+        };
+        return GLWindow::from_backend(
+            backend,
+            descriptor,
+            (version.major as u32, version.minor as u32),
+            profile,
+            context,
+        );
+    }
+
+    /// Wrap the backend of a created window.
+    fn from_backend(
+        backend: WindowBackend,
+        mut descriptor: WindowDescriptor,
+        version: (u32, u32),
+        profile: glfw::OpenGlProfileHint,
+        context: GLContext,
+    ) -> Self {
+        let mut input = InputState::new();
+        let windowed_rect = match &backend {
+            WindowBackend::Glfw { window, .. } => {
+                input.update(&Event::Focused(window.is_focused()));
+                let (x, y) = window.get_pos();
+                (x, y, descriptor.width, descriptor.height)
+            }
+            WindowBackend::Headless { .. } => (0, 0, descriptor.width, descriptor.height),
+        };
+        let gamepad_poller = GamepadPoller::new(descriptor.gamepad_deadzone);
+        descriptor.gamepad_deadzone = gamepad_poller.get_deadzone();
+        return GLWindow {
+            _descriptor: descriptor,
+            _backend: backend,
+            _events: Vec::new(),
+            _input: input,
+            _gamepad_poller: gamepad_poller,
+            _recording: None,
+            _replay: None,
+            _virtual_time: None,
+            _gl_version: version,
+            _gl_profile: profile,
+            _context: context,
+            _windowed_rect: windowed_rect,
+        };
+    }
+
+    /// Generate a builder for the window.
+    pub fn builder() -> GLWindowBuilder {
+        log::info!("GLWindow :: Creating new GLWindow");
+        return GLWindowBuilder::default();
+    }
+
+    /// Create a new window whose context shares objects (buffers, textures,
+    /// shader programs, etc.) with this one, using the same OpenGL version
+    /// and profile.
+    ///
+    /// Vertex arrays and framebuffers are not shared, so each window needs
+    /// its own. If the descriptor makes the new window current, the draws
+    /// that follow go to it until another window is made current.
+    ///
+    /// Headless windows can not share their objects, so this fails for them
+    /// and for headless descriptors.
+    pub fn create_shared(&self, descriptor: WindowDescriptor) -> Result<GLWindow, WindowError> {
+        log::info!("GLWindow :: Creating shared window");
+        let window = match &self._backend {
+            WindowBackend::Glfw { window, .. } if !descriptor.headless => window,
+            _ => {
+                return Err(WindowError::Egl(
+                    "headless windows can not be shared".to_string(),
+                ))
+            }
+        };
+        let handles = init_shared_glfw(window, &descriptor, self._gl_version, self._gl_profile)?;
+        let context = self._context.new_shared();
+        if let Some(capabilities) = self._context.get_capabilities() {
+            context.set_capabilities(capabilities);
+        }
+        if descriptor.debug_context && handles.0.is_current() {
+            context.set_current();
+            enable_debug_output(&context);
+        }
+        return Ok(GLWindow::from_handles(
+            handles,
+            descriptor,
+            self._gl_profile,
+            context,
+        ));
+    }
+
+    /// Make the context of this window the current one, so the following
+    /// OpenGL calls go to it.
+    ///
+    /// Switching contexts invalidates the `StateCache`. The capabilities are
+    /// queried the first time if the window was not current when built.
+    pub fn make_current(&mut self) {
+        if !self.is_current() {
+            match &mut self._backend {
+                WindowBackend::Glfw { window, .. } => window.make_current(),
+                WindowBackend::Headless { context, .. } => {
+                    if let Err(e) = context.make_current() {
+                        log::error!("GLWindow :: {}", e);
+                    }
+                }
+            }
+            StateCache::invalidate();
+        }
+        self._context.set_current();
+        if self._context.get_capabilities().is_none() {
+            self._context
+                .set_capabilities(Rc::new(GLCapabilities::query()));
+        }
+    }
+
+    /// Get whether the context of this window is the current one or not.
+    pub fn is_current(&self) -> bool {
+        return match &self._backend {
+            WindowBackend::Glfw { window, .. } => window.is_current(),
+            WindowBackend::Headless { context, .. } => context.is_current(),
+        };
+    }
+
+    /// Get the GLFW window, unless the window is headless.
+    pub fn get_window(&self) -> Option<&glfw::Window> {
+        return match &self._backend {
+            WindowBackend::Glfw { window, .. } => Some(window),
+            WindowBackend::Headless { .. } => None,
+        };
+    }
+
+    /// Get whether the window is headless or not.
+    pub fn is_headless(&self) -> bool {
+        return matches!(self._backend, WindowBackend::Headless { .. });
+    }
+
+    /// Get the size of the framebuffer in pixels. Headless windows have no
+    /// default framebuffer, so this is the size in their descriptor.
+    pub fn get_framebuffer_size(&self) -> (i32, i32) {
+        return match &self._backend {
+            WindowBackend::Glfw { window, .. } => window.get_framebuffer_size(),
+            WindowBackend::Headless { .. } => (self._descriptor.width, self._descriptor.height),
+        };
+    }
+
+    /// Get the GLFW window descriptor.
+    pub fn get_descriptor(&self) -> &WindowDescriptor {
+        return &self._descriptor;
+    }
+
+    /// Get the OpenGL version of the window's context.
+    pub fn get_version(&self) -> (u32, u32) {
+        return self._gl_version;
+    }
+
+    /// Get the OpenGL profile requested for the window.
+    pub fn get_profile(&self) -> glfw::OpenGlProfileHint {
+        return self._gl_profile;
+    }
+
+    /// Get the context of the window, required to create resources.
+    pub fn get_context(&self) -> &GLContext {
+        return &self._context;
+    }
+
+    /// Get the capabilities of the OpenGL implementation, queried when the
+    /// window is first current. They are all empty until then.
+    pub fn get_capabilities(&self) -> Rc<GLCapabilities> {
+        return self._context.get_capabilities().unwrap_or_default();
+    }
+
+    /// Get the current mode of the window.
+    pub fn get_mode(&self) -> WindowMode {
+        return self._descriptor.mode;
+    }
+
+    /// Switch the mode of the window, e.g. to go fullscreen on another
+    /// monitor. When going back to windowed mode, the last windowed position
+    /// and size are restored.
+    pub fn set_mode(&mut self, mode: WindowMode) -> Result<(), WindowError> {
+        let WindowBackend::Glfw {
+            window, instance, ..
+        } = &mut self._backend
+        else {
+            log::warn!("GLWindow :: Ignoring mode switch on a headless window");
+            return Ok(());
+        };
+        if self._descriptor.mode == WindowMode::Windowed {
+            let (x, y) = window.get_pos();
+            let (width, height) = window.get_size();
+            self._windowed_rect = (x, y, width, height);
+        }
+        set_window_mode(instance, window, mode, self._windowed_rect)?;
+        self._descriptor.mode = mode;
+        return Ok(());
+    }
+
+    /// Set the title of the window.
+    pub fn set_title(&mut self, title: &str) {
+        if let WindowBackend::Glfw { window, .. } = &mut self._backend {
+            window.set_title(title);
+        }
+        self._descriptor.title = title.to_string();
+    }
+
+    /// Set the swap interval. This makes the window's context current, as
+    /// the interval applies to the current context.
+    pub fn set_swap_interval(&mut self, swap_interval: SwapInterval) {
+        log::info!("GLWindow :: Setting swap interval to {:?}", swap_interval);
+        self.make_current();
+        if let WindowBackend::Glfw { instance, .. } = &mut self._backend {
+            instance.set_swap_interval(swap_interval.into());
+        }
+        self._descriptor.swap_interval = swap_interval;
+    }
+
+    /// Enable or disable vsync, i.e. set the swap interval to 1 or 0.
+    pub fn set_vsync(&mut self, vsync: bool) {
+        self.set_swap_interval(if vsync {
+            SwapInterval::Sync(1)
+        } else {
+            SwapInterval::None
+        });
+    }
+
+    /// Set the icon of the window from image files. Several sizes can be
+    /// given, and the system picks the closest one to what it needs. An
+    /// empty list restores the default icon.
+    ///
+    /// Fails without changing the icon if any of the files can not be
+    /// loaded.
+    pub fn set_icon(&mut self, paths: &[&str]) -> Result<(), WindowError> {
+        log::info!("GLWindow :: Setting window icon from {:?}", paths);
+        let images = paths
+            .iter()
+            .map(|p| load_pixel_image(p))
+            .collect::<Result<Vec<_>, _>>()?;
+        if let WindowBackend::Glfw { window, .. } = &mut self._backend {
+            window.set_icon_from_pixels(images);
+        }
+        return Ok(());
+    }
+
+    /// Set the cursor image from an image file, where the hotspot is the
+    /// pixel of the image that points at the cursor position.
+    ///
+    /// Fails without changing the cursor if the file can not be loaded.
+    pub fn set_cursor_image(&mut self, path: &str, hotspot: (u32, u32)) -> Result<(), WindowError> {
+        log::info!("GLWindow :: Setting cursor image from {:?}", path);
+        let image = load_pixel_image(path)?;
+        if let WindowBackend::Glfw { window, .. } = &mut self._backend {
+            let cursor = glfw::Cursor::create_from_pixels(image, hotspot.0, hotspot.1);
+            window.set_cursor(Some(cursor));
+        }
+        return Ok(());
+    }
+
+    /// Restore the default cursor.
+    pub fn reset_cursor(&mut self) {
+        if let WindowBackend::Glfw { window, .. } = &mut self._backend {
+            window.set_cursor(None);
+        }
+    }
+
+    /// Get the contents of the clipboard, if it contains text.
+    pub fn get_clipboard(&self) -> Option<String> {
+        return match &self._backend {
+            WindowBackend::Glfw { window, .. } => window.get_clipboard_string(),
+            WindowBackend::Headless { .. } => None,
+        };
+    }
+
+    /// Set the contents of the clipboard.
+    pub fn set_clipboard(&mut self, contents: &str) {
+        if let WindowBackend::Glfw { window, .. } = &mut self._backend {
+            window.set_clipboard_string(contents);
+        }
+    }
+
+    /// Get the information of every connected monitor, with the primary
+    /// monitor first.
+    pub fn get_monitors(&mut self) -> Vec<MonitorInfo> {
+        return match &mut self._backend {
+            WindowBackend::Glfw { instance, .. } => get_monitors(instance),
+            WindowBackend::Headless { .. } => Vec::new(),
+        };
+    }
+
     /// Get whether the window should close or not.
     pub fn should_close(&self) -> bool {
-        return self._glfw_window.should_close();
+        return match &self._backend {
+            WindowBackend::Glfw { window, .. } => window.should_close(),
+            WindowBackend::Headless { should_close, .. } => *should_close,
+        };
     }
 
     /// Set whether the window should close or not.
     pub fn set_should_close(&mut self, condition: bool) {
-        self._glfw_window.set_should_close(condition);
+        match &mut self._backend {
+            WindowBackend::Glfw { window, .. } => window.set_should_close(condition),
+            WindowBackend::Headless { should_close, .. } => *should_close = condition,
+        }
     }
 
-    /// Poll the events in the window.
+    /// Poll the events in the window, replacing the events of the previous
+    /// poll and starting a new frame in the input state.
+    ///
+    /// While replaying, the events come from the next frame of the recording
+    /// instead, and real input is discarded. The gamepads are still polled,
+    /// so their state is up to date when the replay ends.
+    ///
+    /// Headless windows have no real input, so their events only come from
+    /// replays.
     pub fn poll_events(&mut self) {
-        self._glfw_instance.poll_events();
+        self._events.clear();
+        self._input.begin_frame();
+        let mut live_events: Vec<Event> = Vec::new();
+        if let WindowBackend::Glfw {
+            receiver, instance, ..
+        } = &mut self._backend
+        {
+            instance.poll_events();
+            live_events
+                .extend(glfw::flush_messages(receiver).filter_map(|(_, e)| Event::from_glfw(e)));
+            if self._descriptor.gamepad_polling {
+                live_events.extend(self._gamepad_poller.poll(instance));
+            }
+        }
+
+        match &mut self._replay {
+            Some(replay) => match replay.next_frame().cloned() {
+                Some(frame) => {
+                    self._virtual_time = Some(replay.get_time(&frame));
+                    self._events = frame.events;
+                }
+                None => {
+                    log::info!("GLWindow :: Replay finished");
+                    self._replay = None;
+                    self._virtual_time = None;
+                }
+            },
+            None => self._events = live_events,
+        }
+
+        for event in &self._events {
+            self._input.update(event);
+        }
+        let time = self.get_time_f64();
+        if let Some(recording) = &mut self._recording {
+            recording.push_frame(time, &self._events);
+        }
+    }
+
+    /// Start recording the events of every poll, discarding any recording
+    /// in progress.
+    pub fn start_recording(&mut self) {
+        log::info!("GLWindow :: Starting recording");
+        self._recording = Some(InputRecording::new());
+    }
+
+    /// Stop recording, returning the recording if there was one.
+    pub fn stop_recording(&mut self) -> Option<InputRecording> {
+        log::info!("GLWindow :: Stopping recording");
+        return self._recording.take();
+    }
+
+    /// Get whether the window is recording or not.
+    pub fn is_recording(&self) -> bool {
+        return self._recording.is_some();
+    }
+
+    /// Start replaying a recording. Each poll takes the next frame of the
+    /// recording, and the window time advances as in the recording, starting
+    /// from the current time, until the recording is over.
+    pub fn start_replay(&mut self, recording: InputRecording) {
+        log::info!("GLWindow :: Replaying {:?} frames", recording.frames.len());
+        let time = self.get_time_f64();
+        self._replay = Some(InputReplay::new(recording).start_at(time));
     }
 
-    /// Get the polled events.
-    pub fn get_events(&self) -> &Receiver<(f64, glfw::WindowEvent)> {
+    /// Stop replaying, going back to real input and time.
+    pub fn stop_replay(&mut self) {
+        self._replay = None;
+        self._virtual_time = None;
+    }
+
+    /// Get whether the window is replaying a recording or not.
+    pub fn is_replaying(&self) -> bool {
+        return self._replay.is_some();
+    }
+
+    /// Get the events of the last poll.
+    pub fn get_events(&self) -> &[Event] {
         return &self._events;
     }
 
-    /// Swap the window buffers.
+    /// Get the input state, updated on every poll.
+    pub fn get_input(&self) -> &InputState {
+        return &self._input;
+    }
+
+    /// Get the information of every connected joystick.
+    pub fn get_gamepads(&self) -> Vec<GamepadInfo> {
+        return match &self._backend {
+            WindowBackend::Glfw { instance, .. } => get_gamepads(instance),
+            WindowBackend::Headless { .. } => Vec::new(),
+        };
+    }
+
+    /// Add SDL style gamepad mappings (i.e. lines of `gamecontrollerdb.txt`),
+    /// returning whether they could be parsed or not.
+    pub fn update_gamepad_mappings(&self, mappings: &str) -> bool {
+        return match &self._backend {
+            WindowBackend::Glfw { instance, .. } => update_gamepad_mappings(instance, mappings),
+            WindowBackend::Headless { .. } => false,
+        };
+    }
+
+    /// Set the radius of the deadzone applied to gamepad sticks, which is
+    /// clamped to [0, 0.99].
+    pub fn set_gamepad_deadzone(&mut self, deadzone: f32) {
+        self._gamepad_poller.set_deadzone(deadzone);
+        self._descriptor.gamepad_deadzone = self._gamepad_poller.get_deadzone();
+    }
+
+    /// Swap the window buffers. Headless windows have nothing to swap, so
+    /// the pending commands are flushed instead.
     pub fn swap_buffers(&mut self) {
-        self._glfw_window.swap_buffers();
+        match &mut self._backend {
+            WindowBackend::Glfw { window, .. } => window.swap_buffers(),
+            WindowBackend::Headless { .. } => unsafe {
+                gl_call!(Flush());
+            },
+        }
     }
 
     /// Get the current window time.
     pub fn get_time(&self) -> f32 {
-        return self._glfw_instance.get_time() as f32;
+        return self.get_time_f64() as f32;
     }
 
-    /// Build the window.
-    pub fn build(mut self) -> Self {
-        log::info!("GLWindow :: Building window");
-        let (mut window, events, glfw_inst) =
-            init_glfw(&self._descriptor, self._gl_version, self._gl_profile);
-        init_gl(&mut window);
-        self._glfw_window = window;
-        self._events = events;
-        self._glfw_instance = glfw_inst;
-        return self;
+    /// Get the current window time in double precision. While replaying,
+    /// this is the time of the current frame of the recording, shifted so
+    /// the replay starts at the time it was started.
+    pub fn get_time_f64(&self) -> f64 {
+        return self._virtual_time.unwrap_or_else(|| match &self._backend {
+            WindowBackend::Glfw { instance, .. } => instance.get_time(),
+            WindowBackend::Headless { start, .. } => start.elapsed().as_secs_f64(),
+        });
+    }
+}
+
+impl Drop for GLWindow {
+    fn drop(&mut self) {
+        self._context.destroy();
+        self._context.log_leaks();
     }
 }
 
+/// Load an image file as RGBA pixels for GLFW.
+fn load_pixel_image(path: &str) -> Result<glfw::PixelImage, WindowError> {
+    let (img, width, height) = image::try_load(path).map_err(|e| WindowError::Image {
+        path: path.to_string(),
+        description: e.to_string(),
+    })?;
+    let pixels = img
+        .to_rgba8()
+        .pixels()
+        .map(|p| u32::from_ne_bytes(p.0))
+        .collect();
+    return Ok(glfw::PixelImage {
+        width: width as u32,
+        height: height as u32,
+        pixels,
+    });
+}
+
+/// Get the version of the current context.
+fn get_context_version() -> (u32, u32) {
+    let mut major = 0;
+    let mut minor = 0;
+    unsafe {
+        gl_call!(GetIntegerv(gl::MAJOR_VERSION, &mut major));
+        gl_call!(GetIntegerv(gl::MINOR_VERSION, &mut minor));
+    }
+    return (major as u32, minor as u32);
+}
+
 /// Initialize OpenGL.
 pub fn init_gl(window: &mut glfw::Window) {
     log::info!("init_gl :: Initializing OpenGL");
     gl::load_with(|s| window.get_proc_address(s) as *const _);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pixel_images_are_loaded_as_rgba() {
+        let path = std::env::temp_dir().join("wiener_gl_pixel_image_test.png");
+        let path = path.to_str().unwrap();
+        let rgba = [255, 0, 0, 255, 0, 255, 0, 128];
+        image::save_image_rgba_u8(path, &rgba, 2, 1);
+        let result = load_pixel_image(path);
+        std::fs::remove_file(path).unwrap();
+
+        let image = result.unwrap();
+        assert_eq!((image.width, image.height), (2, 1));
+        assert_eq!(
+            image.pixels,
+            vec![
+                u32::from_ne_bytes([255, 0, 0, 255]),
+                u32::from_ne_bytes([0, 255, 0, 128]),
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_pixel_images_are_an_error() {
+        let path = "missing/icon.png";
+        match load_pixel_image(path) {
+            Err(WindowError::Image {
+                path: error_path, ..
+            }) => assert_eq!(error_path, path),
+            other => panic!("Expected an image error, got {:?}", other.map(|_| ())),
+        }
+    }
+}
@@ -0,0 +1,156 @@
+//! Rendering through a real headless context, which needs EGL and a driver
+//! supporting surfaceless contexts (e.g. Mesa) but no display server. These
+//! tests are ignored by default, run them with
+//! `cargo test -p wiener_gl --test headless -- --ignored`.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+use wiener_core::{Event, InputRecording, WindowDescriptor, WindowError};
+use wiener_gl::prelude::*;
+use wiener_utils::image;
+
+const SIZE: i32 = 64;
+
+const VERTEX_SHADER: &str = "#version 330 core
+layout (location = 0) in vec3 in_position;
+void main() {
+    gl_Position = vec4(in_position, 1.0f);
+}
+";
+
+const FRAGMENT_SHADER: &str = "#version 330 core
+out vec4 frag_color;
+void main() {
+    frag_color = vec4(1.0f, 0.0f, 0.0f, 1.0f);
+}
+";
+
+fn build_headless() -> GLWindow {
+    return GLWindow::builder()
+        .descriptor(WindowDescriptor {
+            width: SIZE,
+            height: SIZE,
+            headless: true,
+            ..Default::default()
+        })
+        .version((3, 3))
+        .build()
+        .expect("Error building the headless window");
+}
+
+#[test]
+#[ignore = "needs EGL and a driver supporting surfaceless contexts"]
+fn headless_window_renders_to_a_framebuffer() {
+    let window = build_headless();
+    assert!(window.is_headless());
+    assert!(window.get_window().is_none());
+    assert_eq!(window.get_framebuffer_size(), (SIZE, SIZE));
+    let context = window.get_context().clone();
+
+    let texture = Texture2D::new(&context).tex_num(0).build();
+    texture.buffer_empty(SIZE, SIZE);
+    let fbo = FrameBuffer::new(&context).attach_texture2d(0, &texture);
+    fbo.verify();
+
+    let shaders = [
+        Shader::new(&context, VERTEX_SHADER, gl::VERTEX_SHADER),
+        Shader::new(&context, FRAGMENT_SHADER, gl::FRAGMENT_SHADER),
+    ];
+    let program = Rc::new(ShaderProgram::from_array(&context, &shaders));
+    let layout = [VertexAttribute::new(0, 3, DataType::Float)];
+    let triangle = Mesh::<f32, u32>::new(program.clone())
+        .vertices(&[-0.5, -0.5, 0.0, 0.5, -0.5, 0.0, 0.0, 0.5, 0.0])
+        .indices(&[0, 1, 2])
+        .layout(&layout);
+
+    fbo.bind();
+    GLManager::viewport(0, 0, SIZE, SIZE);
+    GLManager::clear_color(0.0, 0.0, 1.0, 1.0);
+    GLManager::clear(ClearMask::COLOR);
+    triangle.draw();
+
+    let path = std::env::temp_dir().join("wiener_gl_headless_test.png");
+    let path = path.to_str().unwrap();
+    texture.export((0, 0, SIZE, SIZE), path);
+    let (img, width, height) = image::load(path);
+    std::fs::remove_file(path).unwrap();
+    assert_eq!((width, height), (SIZE, SIZE));
+    let img = img.to_rgba8();
+    let center = SIZE as u32 / 2;
+    assert_eq!(img.get_pixel(center, center).0, [255, 0, 0, 255]);
+    assert_eq!(img.get_pixel(0, 0).0, [0, 0, 255, 255]);
+    assert_eq!(context.get_resource_stats().get_total_count(), 8);
+
+    drop(triangle);
+    drop(program);
+    drop(shaders);
+    drop(fbo);
+    drop(texture);
+    assert_eq!(context.log_leaks(), 0);
+}
+
+/// Updates and events seen by a `ReplayApp`.
+#[derive(Default)]
+struct ReplayLog {
+    updates: usize,
+    events: Vec<Event>,
+}
+
+/// Application that closes the window once the replay is over.
+struct ReplayApp {
+    log: Rc<RefCell<ReplayLog>>,
+}
+
+impl App for ReplayApp {
+    fn update(&mut self, _window: &mut GLWindow, _dt: f32) {
+        self.log.borrow_mut().updates += 1;
+    }
+
+    fn render(&mut self, window: &mut GLWindow, _alpha: f32) {
+        if !window.is_replaying() {
+            window.set_should_close(true);
+        }
+    }
+
+    fn on_event(&mut self, _window: &mut GLWindow, event: &Event) {
+        self.log.borrow_mut().events.push(event.clone());
+    }
+}
+
+#[test]
+#[ignore = "needs EGL and a driver supporting surfaceless contexts"]
+fn run_updates_while_replaying_an_older_recording() {
+    let mut window = build_headless();
+    // Half a second of frames recorded at 60 Hz, all of them earlier than
+    // the clock of the window once the replay starts
+    let mut recording = InputRecording::new();
+    recording.push_frame(0.0, &[Event::Focused(true)]);
+    for frame in 1..30 {
+        recording.push_frame(frame as f64 / 60.0, &[]);
+    }
+    std::thread::sleep(Duration::from_millis(600));
+    assert!(window.get_time_f64() > recording.get_duration());
+    window.start_replay(recording);
+
+    let log = Rc::new(RefCell::new(ReplayLog::default()));
+    run(window, ReplayApp { log: log.clone() });
+    let log = log.borrow();
+    assert!(log.updates >= 27, "only {} updates ran", log.updates);
+    assert_eq!(log.events, vec![Event::Focused(true)]);
+}
+
+#[test]
+#[ignore = "needs EGL and a driver supporting surfaceless contexts"]
+fn missing_icons_and_cursors_are_an_error() {
+    let mut window = build_headless();
+    assert!(matches!(
+        window.set_icon(&["missing/icon.png"]),
+        Err(WindowError::Image { .. })
+    ));
+    assert!(matches!(
+        window.set_cursor_image("missing/cursor.png", (0, 0)),
+        Err(WindowError::Image { .. })
+    ));
+    assert_eq!(window.set_icon(&[]), Ok(()));
+}
@@ -12,6 +12,17 @@ pub fn load(path: &str) -> (image::DynamicImage, i32, i32) {
     return (img_data, width, height);
 }
 
+/// Load an image like `load`, returning an error instead of panicking if the
+/// file can not be opened or decoded.
+pub fn try_load(path: &str) -> Result<(image::DynamicImage, i32, i32), image::ImageError> {
+    let img_data = Reader::open(Path::new(path))?.decode()?;
+
+    let width = img_data.width().try_into().unwrap();
+    let height = img_data.height().try_into().unwrap();
+
+    return Ok((img_data, width, height));
+}
+
 pub fn save_image_rgb_f32(path: &str, buffer: &[f32], width: i32, height: i32) {
     log::info!(
         "save_image :: Saving {:?}x{:?} f32 RGB image to file {:?}",
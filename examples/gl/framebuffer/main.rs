@@ -1,5 +1,6 @@
 use gl;
 use log;
+use std::rc::Rc;
 use wiener::core::WindowDescriptor;
 use wiener::gl::prelude::*;
 use wiener::utils::math;
@@ -17,20 +18,23 @@ fn main() {
             title: "Framebuffer example".to_string(),
             ..Default::default()
         })
-        .build();
+        .build()
+        .expect("Error building the window");
+    let context = window.get_context().clone();
 
     log::debug!("gl_framebuffer :: Enabling depth testing");
-    GLManager::enable(gl::DEPTH_TEST);
+    GLManager::enable(Capability::DepthTest);
 
     log::debug!("gl_framebuffer :: Initializing framebuffer texture");
-    let fbo_texture = Texture2D::default().tex_num(0).build();
+    let fbo_texture = Rc::new(Texture2D::new(&context).tex_num(0).build());
     fbo_texture.buffer_empty(WINDOW_WIDTH, WINDOW_HEIGHT);
 
     log::debug!("gl_framebuffer :: Initializing depth renderbuffer");
-    let fbo_depth = RenderBuffer::new().set_up(gl::DEPTH24_STENCIL8, WINDOW_WIDTH, WINDOW_HEIGHT);
+    let fbo_depth =
+        RenderBuffer::new(&context).set_up(gl::DEPTH24_STENCIL8, WINDOW_WIDTH, WINDOW_HEIGHT);
 
     log::debug!("gl_framebuffer :: Initializing framebuffer");
-    let fbo = FrameBuffer::new()
+    let fbo = FrameBuffer::new(&context)
         .attach_texture2d(0, &fbo_texture)
         .attach_renderbuffer(gl::DEPTH_STENCIL_ATTACHMENT, &fbo_depth);
     fbo.bind();
@@ -38,30 +42,42 @@ fn main() {
 
     log::debug!("gl_framebuffer :: Making framebuffer shader");
     let framebuffer_shader_arr = [
-        Shader::from_file("examples/gl/framebuffer/resources/shaders/framebuffer.vert"),
-        Shader::from_file("examples/gl/framebuffer/resources/shaders/framebuffer.frag"),
+        Shader::from_file(
+            &context,
+            "examples/gl/framebuffer/resources/shaders/framebuffer.vert",
+        ),
+        Shader::from_file(
+            &context,
+            "examples/gl/framebuffer/resources/shaders/framebuffer.frag",
+        ),
     ];
-    let framebuffer_shader = ShaderProgram::from_array(&framebuffer_shader_arr);
+    let framebuffer_shader = Rc::new(ShaderProgram::from_array(&context, &framebuffer_shader_arr));
     framebuffer_shader.uniform_1f("u_screen_x", WINDOW_WIDTH as f32);
     framebuffer_shader.uniform_1f("u_screen_y", WINDOW_HEIGHT as f32);
 
     log::debug!("gl_framebuffer :: Making ship shader");
     let ship_shader_arr = [
-        Shader::from_file("examples/gl/framebuffer/resources/shaders/ship.vert"),
-        Shader::from_file("examples/gl/framebuffer/resources/shaders/ship.frag"),
+        Shader::from_file(
+            &context,
+            "examples/gl/framebuffer/resources/shaders/ship.vert",
+        ),
+        Shader::from_file(
+            &context,
+            "examples/gl/framebuffer/resources/shaders/ship.frag",
+        ),
     ];
-    let ship_shader = ShaderProgram::from_array(&ship_shader_arr);
+    let ship_shader = Rc::new(ShaderProgram::from_array(&context, &ship_shader_arr));
 
     let screen_quad_layout = [
         VertexAttribute {
             location: 0,
             size: 3,
-            data_type: gl::FLOAT,
+            data_type: DataType::Float,
         },
         VertexAttribute {
             location: 1,
             size: 2,
-            data_type: gl::FLOAT,
+            data_type: DataType::Float,
         },
     ];
 
@@ -69,32 +85,31 @@ fn main() {
         VertexAttribute {
             location: 0,
             size: 3,
-            data_type: gl::FLOAT,
+            data_type: DataType::Float,
         },
         VertexAttribute {
             location: 1,
             size: 3,
-            data_type: gl::FLOAT,
+            data_type: DataType::Float,
         },
     ];
 
     log::debug!("gl_framebuffer :: Loading ship mesh");
     let mut ship = Mesh::<f32, u32>::from_file(
         "examples/gl/framebuffer/resources/models/XJ5 X-wing starfighter.obj",
-        &ship_shader,
+        Rc::clone(&ship_shader),
     )
     .layout(&vertex_layout);
 
     log::debug!("gl_framebuffer :: Making screen quad");
-    let screen_quad_textures = [fbo_texture];
-    let screen_quad = Mesh::<f32, u32>::new(&framebuffer_shader)
+    let screen_quad = Mesh::<f32, u32>::new(Rc::clone(&framebuffer_shader))
         .vertices(&[
             -1.0, -1.0, 0.0, 0.0, 0.0, -1.0, 1.0, 0.0, 0.0, 1.0, 1.0, -1.0, 0.0, 1.0, 0.0, 1.0,
             1.0, 0.0, 1.0, 1.0_f32,
         ])
         .indices(&[0, 2, 1, 2, 3, 1])
         .layout(&screen_quad_layout)
-        .textures(&screen_quad_textures);
+        .textures(vec![Rc::clone(&fbo_texture)]);
 
     log::debug!("gl_framebuffer :: Setting clear color");
     GLManager::clear_color(0.1, 0.05, 0.05, 1.0);
@@ -110,11 +125,11 @@ fn main() {
 
         // Set the time
         window_time = window.get_time();
-        viewport = window.get_window().get_framebuffer_size();
+        viewport = window.get_framebuffer_size();
         ship_shader.uniform_1f("u_time", window_time);
 
-        GLManager::clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
-        GLManager::enable(gl::DEPTH_TEST);
+        GLManager::clear(ClearMask::COLOR | ClearMask::DEPTH);
+        GLManager::enable(Capability::DepthTest);
         GLManager::viewport(0, 0, viewport.0, viewport.1);
 
         ship.model_mat = math::matmul(
@@ -126,8 +141,8 @@ fn main() {
         fbo.unbind();
 
         // === Render framebuffer texture to a quad === //
-        GLManager::clear(gl::COLOR_BUFFER_BIT);
-        GLManager::disable(gl::DEPTH_TEST);
+        GLManager::clear(ClearMask::COLOR);
+        GLManager::disable(Capability::DepthTest);
         GLManager::viewport(0, 0, viewport.0, viewport.1);
 
         screen_quad.draw();
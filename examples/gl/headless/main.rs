@@ -0,0 +1,72 @@
+use log;
+use std::rc::Rc;
+use wiener::core::WindowDescriptor;
+use wiener::gl::prelude::*;
+
+const IMAGE_WIDTH: i32 = 512;
+const IMAGE_HEIGHT: i32 = 512;
+
+fn main() {
+    env_logger::init();
+    log::debug!("gl_headless :: Making headless window");
+    let window = GLWindow::builder()
+        .descriptor(WindowDescriptor {
+            width: IMAGE_WIDTH,
+            height: IMAGE_HEIGHT,
+            title: "Headless example".to_string(),
+            headless: true,
+            ..Default::default()
+        })
+        .fallback_versions(&[(4, 5), (3, 3)])
+        .build()
+        .expect("Error building the window");
+    let context = window.get_context().clone();
+
+    log::debug!("gl_headless :: Initializing framebuffer texture");
+    let fbo_texture = Texture2D::new(&context).tex_num(0).build();
+    fbo_texture.buffer_empty(IMAGE_WIDTH, IMAGE_HEIGHT);
+
+    log::debug!("gl_headless :: Initializing framebuffer");
+    let fbo = FrameBuffer::new(&context).attach_texture2d(0, &fbo_texture);
+    fbo.verify();
+
+    log::debug!("gl_headless :: Making triangle shader");
+    let triangle_shader_arr = [
+        Shader::from_file(&context, "examples/gl/headless/resources/triangle.vert"),
+        Shader::from_file(&context, "examples/gl/headless/resources/triangle.frag"),
+    ];
+    let triangle_shader = Rc::new(ShaderProgram::from_array(&context, &triangle_shader_arr));
+
+    log::debug!("gl_headless :: Making triangle mesh");
+    let triangle = Mesh::<f32, u32>::new(Rc::clone(&triangle_shader))
+        .vertices(&[
+            -0.5, -0.5, 0.0, 1.0, 0.0, 0.0, 0.5, -0.5, 0.0, 0.0, 1.0, 0.0, 0.0, 0.5, 0.0, 0.0, 0.0,
+            1.0_f32,
+        ])
+        .indices(&[0, 1, 2])
+        .layout(&[
+            VertexAttribute {
+                location: 0,
+                size: 3,
+                data_type: DataType::Float,
+            },
+            VertexAttribute {
+                location: 1,
+                size: 3,
+                data_type: DataType::Float,
+            },
+        ]);
+
+    log::debug!("gl_headless :: Rendering a single frame to the framebuffer");
+    fbo.bind();
+    GLManager::viewport(0, 0, IMAGE_WIDTH, IMAGE_HEIGHT);
+    GLManager::clear_color(0.1, 0.1, 0.3, 1.0);
+    GLManager::clear(ClearMask::COLOR);
+    triangle.draw();
+
+    log::debug!("gl_headless :: Exporting the frame to a file");
+    fbo_texture.export(
+        (0, 0, IMAGE_WIDTH, IMAGE_HEIGHT),
+        "examples/gl/headless/resources/out.png",
+    );
+}
@@ -1,4 +1,4 @@
-use gl;
+use std::rc::Rc;
 use wiener::core::WindowDescriptor;
 use wiener::gl::prelude::*;
 use wiener::utils::math;
@@ -13,35 +13,43 @@ fn main() {
             title: "Model OFF example".to_string(),
             ..Default::default()
         })
-        .build();
+        .build()
+        .expect("Error building the window");
+    let context = window.get_context().clone();
 
     log::debug!("gl_model_off :: Enabling features");
-    GLManager::enable(gl::DEPTH_TEST);
+    GLManager::enable(Capability::DepthTest);
 
     log::debug!("gl_model_off :: Making ship shader");
     let ship_shader_arr = [
-        Shader::from_file("examples/gl/model_off/resources/shaders/ship.vert"),
-        Shader::from_file("examples/gl/model_off/resources/shaders/ship.frag"),
+        Shader::from_file(
+            &context,
+            "examples/gl/model_off/resources/shaders/ship.vert",
+        ),
+        Shader::from_file(
+            &context,
+            "examples/gl/model_off/resources/shaders/ship.frag",
+        ),
     ];
-    let ship_shader = ShaderProgram::from_array(&ship_shader_arr);
+    let ship_shader = Rc::new(ShaderProgram::from_array(&context, &ship_shader_arr));
 
     let vertex_layout = [
         VertexAttribute {
             location: 0,
             size: 3,
-            data_type: gl::FLOAT,
+            data_type: DataType::Float,
         },
         VertexAttribute {
             location: 1,
             size: 3,
-            data_type: gl::FLOAT,
+            data_type: DataType::Float,
         },
     ];
 
     log::debug!("gl_model_off :: Loading ship mesh");
     let mut ship = Mesh::<f32, u32>::from_file(
         "examples/gl/model_off/resources/models/XJ5 X-wing starfighter.off",
-        &ship_shader,
+        Rc::clone(&ship_shader),
     )
     .layout(&vertex_layout);
 
@@ -58,10 +66,10 @@ fn main() {
 
         // Set the time
         window_time = window.get_time();
-        viewport = window.get_window().get_framebuffer_size();
+        viewport = window.get_framebuffer_size();
         ship_shader.uniform_1f("u_time", window_time);
 
-        GLManager::clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+        GLManager::clear(ClearMask::COLOR | ClearMask::DEPTH);
         GLManager::viewport(0, 0, viewport.0, viewport.1);
 
         ship.model_mat = math::matmul(
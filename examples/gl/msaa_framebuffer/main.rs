@@ -1,5 +1,6 @@
 use gl;
 use log;
+use std::rc::Rc;
 use wiener::core::WindowDescriptor;
 use wiener::gl::prelude::*;
 use wiener::utils::math;
@@ -18,34 +19,40 @@ fn main() {
             title: "Framebuffer example".to_string(),
             ..Default::default()
         })
-        .build();
+        .build()
+        .expect("Error building the window");
+    let context = window.get_context().clone();
 
     log::debug!("gl_msaa_framebuffer :: Enabling depth testing");
-    GLManager::enable(gl::DEPTH_TEST);
+    GLManager::enable(Capability::DepthTest);
+
+    let samples = window.get_capabilities().clamp_samples(MSAA_SAMPLES);
+    log::debug!("gl_msaa_framebuffer :: Using {:?} samples", samples);
 
     log::debug!("gl_msaa_framebuffer :: Initializing MSAA texture");
-    let msaa_texture = Texture2D::default().tex_num(0);
-    msaa_texture.buffer_multisampled(MSAA_SAMPLES, WINDOW_WIDTH, WINDOW_HEIGHT);
+    let msaa_texture = Texture2D::new(&context).tex_num(0);
+    msaa_texture.buffer_multisampled(samples, WINDOW_WIDTH, WINDOW_HEIGHT);
     log::debug!("gl_msaa_framebuffer :: Initializing MSAA renderbuffer");
-    let msaa_depth_rbo = RenderBuffer::new().set_up_multisample(
-        MSAA_SAMPLES,
+    let msaa_depth_rbo = RenderBuffer::new(&context).set_up_multisample(
+        samples,
         gl::DEPTH24_STENCIL8,
         WINDOW_WIDTH,
         WINDOW_HEIGHT,
     );
     log::debug!("gl_msaa_framebuffer :: Initializing MSAA framebuffer");
-    let msaa_fbo = FrameBuffer::new()
+    let msaa_fbo = FrameBuffer::new(&context)
         .attach_multisampled_texture2d(0, &msaa_texture)
         .attach_renderbuffer(gl::DEPTH_STENCIL_ATTACHMENT, &msaa_depth_rbo);
 
     log::debug!("gl_msaa_framebuffer :: Initializing framebuffer texture");
-    let fbo_texture = Texture2D::default().tex_num(0).build();
+    let fbo_texture = Rc::new(Texture2D::new(&context).tex_num(0).build());
     fbo_texture.buffer_empty(WINDOW_WIDTH, WINDOW_HEIGHT);
     log::debug!("gl_msaa_framebuffer :: Initializing depth renderbuffer");
-    let fbo_depth = RenderBuffer::new().set_up(gl::DEPTH24_STENCIL8, WINDOW_WIDTH, WINDOW_HEIGHT);
+    let fbo_depth =
+        RenderBuffer::new(&context).set_up(gl::DEPTH24_STENCIL8, WINDOW_WIDTH, WINDOW_HEIGHT);
 
     log::debug!("gl_msaa_framebuffer :: Initializing framebuffer");
-    let fbo = FrameBuffer::new()
+    let fbo = FrameBuffer::new(&context)
         .attach_texture2d(0, &fbo_texture)
         .attach_renderbuffer(gl::DEPTH_STENCIL_ATTACHMENT, &fbo_depth);
     fbo.bind();
@@ -53,30 +60,42 @@ fn main() {
 
     log::debug!("gl_msaa_framebuffer :: Making framebuffer shader");
     let framebuffer_shader_arr = [
-        Shader::from_file("examples/gl/msaa_framebuffer/resources/shaders/framebuffer.vert"),
-        Shader::from_file("examples/gl/msaa_framebuffer/resources/shaders/framebuffer.frag"),
+        Shader::from_file(
+            &context,
+            "examples/gl/msaa_framebuffer/resources/shaders/framebuffer.vert",
+        ),
+        Shader::from_file(
+            &context,
+            "examples/gl/msaa_framebuffer/resources/shaders/framebuffer.frag",
+        ),
     ];
-    let framebuffer_shader = ShaderProgram::from_array(&framebuffer_shader_arr);
+    let framebuffer_shader = Rc::new(ShaderProgram::from_array(&context, &framebuffer_shader_arr));
     framebuffer_shader.uniform_1f("u_screen_x", WINDOW_WIDTH as f32);
     framebuffer_shader.uniform_1f("u_screen_y", WINDOW_HEIGHT as f32);
 
     log::debug!("gl_msaa_framebuffer :: Making ship shader");
     let ship_shader_arr = [
-        Shader::from_file("examples/gl/msaa_framebuffer/resources/shaders/ship.vert"),
-        Shader::from_file("examples/gl/msaa_framebuffer/resources/shaders/ship.frag"),
+        Shader::from_file(
+            &context,
+            "examples/gl/msaa_framebuffer/resources/shaders/ship.vert",
+        ),
+        Shader::from_file(
+            &context,
+            "examples/gl/msaa_framebuffer/resources/shaders/ship.frag",
+        ),
     ];
-    let ship_shader = ShaderProgram::from_array(&ship_shader_arr);
+    let ship_shader = Rc::new(ShaderProgram::from_array(&context, &ship_shader_arr));
 
     let screen_quad_layout = [
         VertexAttribute {
             location: 0,
             size: 3,
-            data_type: gl::FLOAT,
+            data_type: DataType::Float,
         },
         VertexAttribute {
             location: 1,
             size: 2,
-            data_type: gl::FLOAT,
+            data_type: DataType::Float,
         },
     ];
 
@@ -84,32 +103,31 @@ fn main() {
         VertexAttribute {
             location: 0,
             size: 3,
-            data_type: gl::FLOAT,
+            data_type: DataType::Float,
         },
         VertexAttribute {
             location: 1,
             size: 3,
-            data_type: gl::FLOAT,
+            data_type: DataType::Float,
         },
     ];
 
     log::debug!("gl_msaa_framebuffer :: Loading ship mesh");
     let mut ship = Mesh::<f32, u32>::from_file(
         "examples/gl/msaa_framebuffer/resources/models/XJ5 X-wing starfighter.obj",
-        &ship_shader,
+        Rc::clone(&ship_shader),
     )
     .layout(&vertex_layout);
 
     log::debug!("gl_msaa_framebuffer :: Making screen quad");
-    let screen_quad_textures = [fbo_texture];
-    let screen_quad = Mesh::<f32, u32>::new(&framebuffer_shader)
+    let screen_quad = Mesh::<f32, u32>::new(Rc::clone(&framebuffer_shader))
         .vertices(&[
             -1.0, -1.0, 0.0, 0.0, 0.0, -1.0, 1.0, 0.0, 0.0, 1.0, 1.0, -1.0, 0.0, 1.0, 0.0, 1.0,
             1.0, 0.0, 1.0, 1.0_f32,
         ])
         .indices(&[0, 2, 1, 2, 3, 1])
         .layout(&screen_quad_layout)
-        .textures(&screen_quad_textures);
+        .textures(vec![Rc::clone(&fbo_texture)]);
 
     log::debug!("gl_msaa_framebuffer :: Setting clear color");
     GLManager::clear_color(0.1, 0.05, 0.05, 1.0);
@@ -126,8 +144,8 @@ fn main() {
         window_time = window.get_time();
         ship_shader.uniform_1f("u_time", window_time);
 
-        GLManager::clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
-        GLManager::enable(gl::DEPTH_TEST);
+        GLManager::clear(ClearMask::COLOR | ClearMask::DEPTH);
+        GLManager::enable(Capability::DepthTest);
 
         ship.model_mat = math::matmul(
             math::rotation(ROTATION_SPEED * window_time, 0.0, 0.0),
@@ -145,8 +163,8 @@ fn main() {
         );
 
         // === Render framebuffer texture to a quad === //
-        GLManager::clear(gl::COLOR_BUFFER_BIT);
-        GLManager::disable(gl::DEPTH_TEST);
+        GLManager::clear(ClearMask::COLOR);
+        GLManager::disable(Capability::DepthTest);
 
         screen_quad.draw();
 
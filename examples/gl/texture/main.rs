@@ -1,5 +1,6 @@
 use gl;
 use log;
+use std::rc::Rc;
 use wiener::core::WindowDescriptor;
 use wiener::gl::prelude::*;
 use wiener::utils::math;
@@ -14,47 +15,48 @@ fn main() {
             title: "Texture example".to_string(),
             ..Default::default()
         })
-        .build();
+        .build()
+        .expect("Error building the window");
+    let context = window.get_context().clone();
 
     log::debug!("gl_texture :: Making quad shader");
     let quad_shader_arr = [
-        Shader::from_file("examples/gl/texture/resources/shaders/quad.vert"),
-        Shader::from_file("examples/gl/texture/resources/shaders/quad.frag"),
+        Shader::from_file(&context, "examples/gl/texture/resources/shaders/quad.vert"),
+        Shader::from_file(&context, "examples/gl/texture/resources/shaders/quad.frag"),
     ];
-    let quad_shader = ShaderProgram::from_array(&quad_shader_arr);
+    let quad_shader = Rc::new(ShaderProgram::from_array(&context, &quad_shader_arr));
 
     let quad_layout = [
         VertexAttribute {
             location: 0,
             size: 3,
-            data_type: gl::FLOAT,
+            data_type: DataType::Float,
         },
         VertexAttribute {
             location: 1,
             size: 3,
-            data_type: gl::FLOAT,
+            data_type: DataType::Float,
         },
         VertexAttribute {
             location: 2,
             size: 2,
-            data_type: gl::FLOAT,
+            data_type: DataType::Float,
         },
     ];
 
     log::debug!("gl_texture :: Loading quad texture");
-    let quad_texture = Texture2D::default().tex_num(0).format(gl::RGBA).build();
+    let quad_texture = Rc::new(Texture2D::new(&context).tex_num(0).format(gl::RGBA).build());
     quad_texture.buffer_from_file("examples/gl/texture/resources/textures/chihuahua.jpg");
-    let quad_textures = [quad_texture];
 
     log::debug!("gl_texture :: Making quad mesh");
-    let mut quad = Mesh::<f32, u32>::new(&quad_shader)
+    let mut quad = Mesh::<f32, u32>::new(Rc::clone(&quad_shader))
         .vertices(&[
             -0.5, -0.5, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, -0.5, 0.5, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.5,
             -0.5, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 0.5, 0.5, 0.0, 1.0, 1.0, 1.0, 1.0, 0.0_f32,
         ])
         .indices(&[0, 2, 1, 2, 3, 1])
         .layout(&quad_layout)
-        .textures(&quad_textures);
+        .textures(vec![quad_texture]);
 
     log::debug!("gl_texture :: Setting clear color");
     GLManager::clear_color(0.1, 0.1, 0.3, 1.0);
@@ -72,9 +74,9 @@ fn main() {
         window_time = window.get_time();
         quad_shader.uniform_1f("u_time", window_time);
 
-        viewport = window.get_window().get_framebuffer_size();
+        viewport = window.get_framebuffer_size();
 
-        GLManager::clear(gl::COLOR_BUFFER_BIT);
+        GLManager::clear(ClearMask::COLOR);
         GLManager::viewport(0, 0, viewport.0, viewport.1);
 
         quad.model_mat = math::rotation(
@@ -1,5 +1,5 @@
-use gl;
 use log;
+use std::rc::Rc;
 use wiener::core::WindowDescriptor;
 use wiener::gl::prelude::*;
 
@@ -13,17 +13,19 @@ fn main() {
             title: "Triangle example".to_string(),
             ..Default::default()
         })
-        .build();
+        .build()
+        .expect("Error building the window");
+    let context = window.get_context().clone();
 
     log::debug!("gl_triangle :: Making triangle shader");
     let triangle_shader_arr = [
-        Shader::from_file("examples/gl/triangle/resources/triangle.vert"),
-        Shader::from_file("examples/gl/triangle/resources/triangle.frag"),
+        Shader::from_file(&context, "examples/gl/triangle/resources/triangle.vert"),
+        Shader::from_file(&context, "examples/gl/triangle/resources/triangle.frag"),
     ];
-    let triangle_shader = ShaderProgram::from_array(&triangle_shader_arr);
+    let triangle_shader = Rc::new(ShaderProgram::from_array(&context, &triangle_shader_arr));
 
     log::debug!("gl_triangle :: Making triangle mesh");
-    let triangle = Mesh::<f32, u32>::new(&triangle_shader)
+    let triangle = Mesh::<f32, u32>::new(Rc::clone(&triangle_shader))
         .vertices(&[
             -0.5, -0.5, 0.0, 1.0, 0.0, 0.0, 0.5, -0.5, 0.0, 0.0, 1.0, 0.0, 0.0, 0.5, 0.0, 0.0, 0.0,
             1.0_f32,
@@ -33,12 +35,12 @@ fn main() {
             VertexAttribute {
                 location: 0,
                 size: 3,
-                data_type: gl::FLOAT,
+                data_type: DataType::Float,
             },
             VertexAttribute {
                 location: 1,
                 size: 3,
-                data_type: gl::FLOAT,
+                data_type: DataType::Float,
             },
         ]);
 
@@ -49,8 +51,8 @@ fn main() {
     let mut viewport;
     while !window.should_close() {
         window.poll_events();
-        viewport = window.get_window().get_framebuffer_size();
-        GLManager::clear(gl::COLOR_BUFFER_BIT);
+        viewport = window.get_framebuffer_size();
+        GLManager::clear(ClearMask::COLOR);
         GLManager::viewport(0, 0, viewport.0, viewport.1);
 
         triangle.draw();
@@ -1,5 +1,5 @@
-use gl;
 use log;
+use std::rc::Rc;
 use wiener::core::WindowDescriptor;
 use wiener::gl::prelude::*;
 use wiener::utils::math;
@@ -14,30 +14,32 @@ fn main() {
             title: "Uniforms example".to_string(),
             ..Default::default()
         })
-        .build();
+        .build()
+        .expect("Error building the window");
+    let context = window.get_context().clone();
 
     log::debug!("gl_uniform :: Making triangle shader");
     let triangle_shader_arr = [
-        Shader::from_file("examples/gl/uniform/resources/triangle.vert"),
-        Shader::from_file("examples/gl/uniform/resources/triangle.frag"),
+        Shader::from_file(&context, "examples/gl/uniform/resources/triangle.vert"),
+        Shader::from_file(&context, "examples/gl/uniform/resources/triangle.frag"),
     ];
-    let triangle_shader = ShaderProgram::from_array(&triangle_shader_arr);
+    let triangle_shader = Rc::new(ShaderProgram::from_array(&context, &triangle_shader_arr));
 
     let triangle_layout = [
         VertexAttribute {
             location: 0,
             size: 3,
-            data_type: gl::FLOAT,
+            data_type: DataType::Float,
         },
         VertexAttribute {
             location: 1,
             size: 3,
-            data_type: gl::FLOAT,
+            data_type: DataType::Float,
         },
     ];
 
     log::debug!("gl_uniform :: Making triangle mesh");
-    let mut triangle_rotation = Mesh::<f32, u32>::new(&triangle_shader)
+    let mut triangle_rotation = Mesh::<f32, u32>::new(Rc::clone(&triangle_shader))
         .vertices(&[
             -0.5, -0.5, 0.0, 1.0, 0.0, 0.0, 0.5, -0.5, 0.0, 0.0, 1.0, 0.0, 0.0, 0.5, 0.0, 0.0, 0.0,
             1.0_f32,
@@ -45,7 +47,7 @@ fn main() {
         .indices(&[0, 1, 2])
         .layout(&triangle_layout);
 
-    let mut triangle_translation = Mesh::<f32, u32>::new(&triangle_shader)
+    let mut triangle_translation = Mesh::<f32, u32>::new(Rc::clone(&triangle_shader))
         .vertices(&[
             -0.5, -0.5, 0.0, 1.0, 0.0, 0.0, 0.5, -0.5, 0.0, 0.0, 1.0, 0.0, 0.0, 0.5, 0.0, 0.0, 0.0,
             1.0_f32,
@@ -69,9 +71,9 @@ fn main() {
         window_time = window.get_time();
         triangle_shader.uniform_1f("u_time", window_time);
 
-        viewport = window.get_window().get_framebuffer_size();
+        viewport = window.get_framebuffer_size();
 
-        GLManager::clear(gl::COLOR_BUFFER_BIT);
+        GLManager::clear(ClearMask::COLOR);
         GLManager::viewport(0, 0, viewport.0, viewport.1);
 
         triangle_rotation.model_mat =
@@ -1,7 +1,41 @@
-use gl;
-use wiener::core::WindowDescriptor;
+use wiener::core::{Action, Event, Key, WindowDescriptor};
 use wiener::gl::prelude::*;
 
+/// Application that pulses the clear color.
+struct WindowApp {
+    previous_phase: f32,
+    phase: f32,
+}
+
+impl App for WindowApp {
+    fn init(&mut self, _window: &mut GLWindow) {
+        log::debug!("gl_window :: Setting clear color");
+        GLManager::clear_color(0.1, 0.1, 0.3, 1.0);
+    }
+
+    fn update(&mut self, _window: &mut GLWindow, dt: f32) {
+        self.previous_phase = self.phase;
+        self.phase += dt;
+    }
+
+    fn on_event(&mut self, window: &mut GLWindow, event: &Event) {
+        if let Event::Key {
+            key: Key::Escape,
+            action: Action::Press,
+            ..
+        } = event
+        {
+            window.set_should_close(true);
+        }
+    }
+
+    fn render(&mut self, _window: &mut GLWindow, alpha: f32) {
+        let phase = self.previous_phase + (self.phase - self.previous_phase) * alpha;
+        GLManager::clear_color(0.1, 0.1, 0.3 + 0.2 * phase.sin(), 1.0);
+        GLManager::clear(ClearMask::COLOR);
+    }
+}
+
 fn main() {
     env_logger::init();
     log::debug!("gl_window :: Making window");
@@ -12,16 +46,24 @@ fn main() {
             title: "Window example".to_string(),
             ..Default::default()
         })
-        .build();
+        .fallback_versions(&[(4, 5), (3, 3)])
+        .build()
+        .expect("Error building the window");
 
-    log::debug!("gl_window :: Setting clear color");
-    GLManager::clear_color(0.1, 0.1, 0.3, 1.0);
-
-    log::debug!("gl_window :: Starting the render loop");
-    while !window.should_close() {
-        window.poll_events();
-        GLManager::clear(gl::COLOR_BUFFER_BIT);
-
-        window.swap_buffers();
+    for monitor in window.get_monitors() {
+        log::debug!(
+            "gl_window :: Found monitor {:?} with mode {:?}",
+            monitor.name,
+            monitor.current_mode
+        );
     }
+
+    log::debug!("gl_window :: Starting the application");
+    run(
+        window,
+        WindowApp {
+            previous_phase: 0.0,
+            phase: 0.0,
+        },
+    );
 }